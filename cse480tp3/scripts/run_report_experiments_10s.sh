@@ -0,0 +1,37 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+# Builds the solver, reruns every 10 s report experiment (several minutes) and writes the
+# text reports, CSV runs and LaTeX tables into results_10s/.
+
+ROOT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)"
+RESULTS_DIR="${ROOT_DIR}/results_10s"
+DATA_DIR="${ROOT_DIR}/../datasets"
+RUNS="${RUNS:-5}"
+TIME_LIMIT_S="${TIME_LIMIT_S:-10}"
+
+mkdir -p "${RESULTS_DIR}"
+cd "${ROOT_DIR}"
+cargo build --release
+BIN="${ROOT_DIR}/target/release/cse480tp3"
+COMMON=(--runs "${RUNS}" --time-limit-s "${TIME_LIMIT_S}")
+
+"${BIN}" report-file "${DATA_DIR}/TP2_example.bpp.txt" "${COMMON[@]}" \
+  --latex "${RESULTS_DIR}/tp2_table.tex" --latex-style table \
+  --caption "Results on TP2 example instance, ${RUNS} runs" \
+  > "${RESULTS_DIR}/tp2_report.txt"
+
+"${BIN}" report-batch "${COMMON[@]}" \
+  --latex "${RESULTS_DIR}/synthetic_table.tex" --latex-style table \
+  --caption "Results on the default batch (TP2 and synthetic instances), ${RUNS} runs each" \
+  > "${RESULTS_DIR}/batch_report.txt"
+
+for name in binpack2 binpack4 binpack7 binpack8; do
+  "${BIN}" report-file "${DATA_DIR}/${name}.txt" "${COMMON[@]}" \
+    --latex "${RESULTS_DIR}/${name}_longtable.tex" \
+    --csv "${RESULTS_DIR}/${name}_runs.csv" \
+    > "${RESULTS_DIR}/${name}_report.txt"
+done
+
+echo "Wrote:"
+ls -1 "${RESULTS_DIR}"/*_table.tex "${RESULTS_DIR}"/*_longtable.tex
@@ -0,0 +1,278 @@
+use std::time::{Duration, Instant};
+
+use crate::bounds::best_lower_bound;
+use crate::instances::Instance;
+use crate::packing::{packing_objective, try_reduce_bins, DecoderKind, Objective, PrefixCache};
+use crate::rng::XorShift64;
+use crate::solver::Solver;
+use crate::tabu::{apply_insert, apply_swap, decreasing_order, iters_per_sec, StopReason, TabuResult};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cooling {
+    // T <- alpha * T after every temperature level.
+    Geometric { alpha: f64 },
+    // T falls by a constant step so that it would reach final_temp at max_iters.
+    Linear,
+    // Cools fast while more than `target_accept` of the moves are accepted, slowly otherwise.
+    Adaptive { target_accept: f64 },
+}
+
+impl Cooling {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cooling::Geometric { .. } => "geometric",
+            Cooling::Linear => "linear",
+            Cooling::Adaptive { .. } => "adaptive",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "geometric" => Some(Cooling::Geometric { alpha: 0.95 }),
+            "linear" => Some(Cooling::Linear),
+            "adaptive" => Some(Cooling::Adaptive { target_accept: 0.3 }),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct AnnealingParams {
+    // One iteration is one evaluated move.
+    pub max_iters: u32,
+    pub time_limit: Option<Duration>,
+    // None estimates T0 so that about 80% of sampled uphill moves would be accepted.
+    pub initial_temp: Option<f64>,
+    pub final_temp: f64,
+    pub moves_per_temp: u32,
+    pub cooling: Cooling,
+    // Temperature levels without a new best before reheating; 0 disables reheating.
+    pub reheat_after: u32,
+    // Reheating restarts from the best order at reheat_factor * T0.
+    pub reheat_factor: f64,
+    pub decoder: DecoderKind,
+    pub objective: Objective,
+}
+
+impl Default for AnnealingParams {
+    fn default() -> Self {
+        Self {
+            max_iters: 1_000_000,
+            time_limit: None,
+            initial_temp: None,
+            final_temp: 1e-3,
+            moves_per_temp: 1_000,
+            cooling: Cooling::Geometric { alpha: 0.95 },
+            reheat_after: 50,
+            reheat_factor: 0.5,
+            decoder: DecoderKind::BestFit,
+            objective: Objective::default(),
+        }
+    }
+}
+
+impl Solver for AnnealingParams {
+    fn name(&self) -> String {
+        "annealing".to_string()
+    }
+
+    fn solve(&self, instance: &Instance, seed: u64) -> TabuResult {
+        simulated_annealing(instance, seed, *self)
+    }
+}
+
+fn random_move(instance: &Instance, order: &[usize], rng: &mut XorShift64) -> Option<(Vec<usize>, usize)> {
+    let n = order.len();
+    let move_is_swap = rng.gen_f64() < 0.6;
+    let i = rng.gen_range_usize(n);
+    let j = rng.gen_range_usize(n);
+    if i == j || (move_is_swap && instance.sizes[order[i]] == instance.sizes[order[j]]) {
+        return None;
+    }
+    let candidate = if move_is_swap {
+        apply_swap(order, i, j)
+    } else {
+        apply_insert(order, i, j)
+    };
+    Some((candidate, i.min(j)))
+}
+
+fn estimate_initial_temp(
+    instance: &Instance,
+    cache: &PrefixCache,
+    objective: Objective,
+    current_energy: f64,
+    rng: &mut XorShift64,
+) -> f64 {
+    let mut uphill = Vec::new();
+    for _ in 0..100 {
+        let Some((candidate, first)) = random_move(instance, cache.order(), rng) else {
+            continue;
+        };
+        let pack = try_reduce_bins(instance, &cache.evaluate(instance, &candidate, first));
+        let delta = objective.energy(&pack) - current_energy;
+        if delta > 0.0 {
+            uphill.push(delta);
+        }
+    }
+    if uphill.is_empty() {
+        return 1.0;
+    }
+    let mean = uphill.iter().sum::<f64>() / (uphill.len() as f64);
+    -mean / 0.8f64.ln()
+}
+
+// Simulated annealing on item permutations, decoded like in `tabu_search` and using the same
+// swap/insert moves. Returns a `TabuResult` so the experiment runners can treat both alike.
+pub fn simulated_annealing(instance: &Instance, seed: u64, params: AnnealingParams) -> TabuResult {
+    let n = instance.sizes.len();
+    let start = Instant::now();
+    let mut rng = XorShift64::new(seed);
+
+    let mut current = decreasing_order(instance, &mut rng);
+    let mut cache = PrefixCache::new(instance, params.decoder.decoder(), &current);
+    let mut current_pack = try_reduce_bins(instance, &cache.packing(instance));
+    let mut current_energy = params.objective.energy(&current_pack);
+
+    let mut best_order = current.clone();
+    let mut best_pack = current_pack.clone();
+    let mut best_energy = current_energy;
+
+    let t0 = match params.initial_temp {
+        Some(t) => t,
+        None => estimate_initial_temp(instance, &cache, params.objective, current_energy, &mut rng),
+    };
+    let final_temp = params.final_temp.min(t0);
+    let moves_per_temp = params.moves_per_temp.max(1);
+    let levels = (params.max_iters / moves_per_temp).max(1);
+    let linear_step = (t0 - final_temp) / (levels as f64);
+
+    let mut temp = t0;
+    let mut level_moves: u32 = 0;
+    let mut level_accepted: u32 = 0;
+    let mut levels_since_best: u32 = 0;
+    let mut improved_in_level = false;
+
+    let lb = best_lower_bound(instance);
+    let mut last_it = 0;
+    let mut reason = StopReason::MaxIters;
+
+    for it in 1..=params.max_iters {
+        if best_pack.n_bins() == lb || n < 2 {
+            reason = StopReason::LowerBound;
+            break;
+        }
+        last_it = it;
+        if let Some(limit) = params.time_limit {
+            if start.elapsed() >= limit {
+                reason = StopReason::TimeLimit;
+                break;
+            }
+        }
+
+        if let Some((candidate, first)) = random_move(instance, &current, &mut rng) {
+            let pack = try_reduce_bins(instance, &cache.evaluate(instance, &candidate, first));
+            let energy = params.objective.energy(&pack);
+            let delta = energy - current_energy;
+            let accept = delta <= 0.0 || (temp > 0.0 && rng.gen_f64() < (-delta / temp).exp());
+            if accept {
+                current = candidate;
+                cache.rebuild_from(instance, &current, first);
+                current_pack = pack;
+                current_energy = energy;
+                level_accepted += 1;
+
+                if current_energy < best_energy {
+                    best_energy = current_energy;
+                    best_order = current.clone();
+                    best_pack = current_pack.clone();
+                    improved_in_level = true;
+                }
+            }
+        }
+
+        level_moves += 1;
+        if level_moves < moves_per_temp {
+            continue;
+        }
+
+        let accept_rate = level_accepted as f64 / level_moves as f64;
+        temp = match params.cooling {
+            Cooling::Geometric { alpha } => temp * alpha,
+            Cooling::Linear => temp - linear_step,
+            Cooling::Adaptive { target_accept } => {
+                if accept_rate > target_accept {
+                    temp * 0.9
+                } else {
+                    temp * 0.99
+                }
+            }
+        }
+        .max(final_temp);
+
+        levels_since_best = if improved_in_level { 0 } else { levels_since_best + 1 };
+        if params.reheat_after > 0 && levels_since_best >= params.reheat_after {
+            temp = (t0 * params.reheat_factor).max(final_temp);
+            current = best_order.clone();
+            cache.rebuild_from(instance, &current, 0);
+            current_energy = best_energy;
+            levels_since_best = 0;
+        }
+
+        level_moves = 0;
+        level_accepted = 0;
+        improved_in_level = false;
+    }
+
+    let elapsed = start.elapsed();
+    let (best_bins, best_unused) = packing_objective(&best_pack);
+    TabuResult {
+        best_order,
+        best_packing: best_pack,
+        best_bins,
+        best_unused,
+        elapsed,
+        iters: last_it,
+        iters_per_sec: iters_per_sec(last_it, elapsed),
+        stop_reason: reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::packing::validate_packing;
+
+    #[test]
+    fn every_cooling_schedule_returns_valid_packings() {
+        let inst = synthetic_instance("annealing", 80, 150, 10, 100, 6);
+        for cooling in [
+            Cooling::Geometric { alpha: 0.9 },
+            Cooling::Linear,
+            Cooling::Adaptive { target_accept: 0.3 },
+        ] {
+            let params = AnnealingParams {
+                max_iters: 5_000,
+                moves_per_temp: 100,
+                reheat_after: 5,
+                cooling,
+                ..AnnealingParams::default()
+            };
+            let res = simulated_annealing(&inst, 3, params);
+            validate_packing(&inst, &res.best_packing).unwrap();
+            assert!(res.best_bins >= best_lower_bound(&inst), "{}", cooling.name());
+        }
+    }
+
+    #[test]
+    fn tp2_example_reaches_optimum() {
+        let inst = example_instance_tp2();
+        let params = AnnealingParams {
+            max_iters: 20_000,
+            ..AnnealingParams::default()
+        };
+        let res = simulated_annealing(&inst, 0, params);
+        assert_eq!(res.best_bins, 4);
+    }
+}
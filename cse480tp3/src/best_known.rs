@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+
+use crate::instances::Instance;
+use crate::mip::sanitize;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KnownSolution {
+    pub name: String,
+    pub bins: usize,
+    pub proven: bool,
+}
+
+fn parse_flag(tok: &str) -> Option<bool> {
+    match tok.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "opt" | "optimal" | "proven" => Some(true),
+        "0" | "false" | "no" | "ub" | "feasible" => Some(false),
+        _ => None,
+    }
+}
+
+// One "<instance name> <bins> <proven>" per line; `#` starts a comment.
+pub fn parse_best_known(content: &str) -> Result<Vec<KnownSolution>, String> {
+    let mut out = Vec::new();
+    for (lineno, line) in content.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+        let toks: Vec<&str> = line.split_whitespace().collect();
+        if toks.len() != 3 {
+            return Err(format!("line {}: expected `name bins proven`, got `{line}`", lineno + 1));
+        }
+        let bins = toks[1]
+            .parse::<usize>()
+            .map_err(|_| format!("line {}: invalid bin count `{}`", lineno + 1, toks[1]))?;
+        let proven =
+            parse_flag(toks[2]).ok_or_else(|| format!("line {}: invalid proven flag `{}`", lineno + 1, toks[2]))?;
+        out.push(KnownSolution {
+            name: toks[0].to_string(),
+            bins,
+            proven,
+        });
+    }
+    Ok(out)
+}
+
+fn first_number(s: &str) -> Option<f64> {
+    s.split(|c: char| c.is_whitespace() || c == '=' || c == ':' || c == '"' || c == '(')
+        .find_map(|tok| tok.parse::<f64>().ok())
+}
+
+// Objective value and status of a MIP solution file. Understands the CPLEX XML .sol, Gurobi,
+// CBC, SCIP, HiGHS and GLPK (printable) outputs; only an explicitly optimal status counts as
+// proven.
+pub fn parse_mip_solution(name: &str, content: &str) -> Result<KnownSolution, String> {
+    let mut objective: Option<f64> = None;
+    let mut proven = false;
+    let mut after_model_status = false;
+
+    for line in content.lines() {
+        let lower = line.trim().to_ascii_lowercase();
+        if objective.is_none() {
+            if let Some(pos) = lower.find("objectivevalue=") {
+                objective = first_number(&lower[pos + "objectivevalue=".len()..]);
+            } else if let Some(pos) = lower.find("objective value") {
+                objective = first_number(&lower[pos + "objective value".len()..]);
+            } else if lower.starts_with("objective:") || lower.starts_with("objective ") {
+                let rest = lower.split_once('=').map_or(&lower["objective".len()..], |(_, r)| r);
+                objective = first_number(rest);
+            }
+        }
+
+        let optimal_status = (lower.contains("solutionstatusstring=\"integer optimal") && !lower.contains("tolerance"))
+            || lower.starts_with("optimal - objective value")
+            || lower.contains("optimal solution found")
+            || (lower.starts_with("status:") && lower.contains("integer optimal"))
+            || (after_model_status && lower == "optimal");
+        proven |= optimal_status;
+        after_model_status = lower == "model status";
+    }
+
+    let objective = objective.ok_or_else(|| format!("{name}: no objective value found"))?;
+    if objective < 0.0 {
+        return Err(format!("{name}: negative objective value {objective}"));
+    }
+    Ok(KnownSolution {
+        name: name.to_string(),
+        bins: objective.round() as usize,
+        proven,
+    })
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+fn is_sol(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("sol")
+}
+
+// A `.sol` file (named after its instance), a directory of them, or a best-known-solutions file.
+pub fn load_known_solutions(path: impl AsRef<Path>) -> Result<Vec<KnownSolution>, String> {
+    let path = path.as_ref();
+    if path.is_dir() {
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for ent in std::fs::read_dir(path).map_err(|e| format!("Failed to read dir {}: {e}", path.display()))? {
+            let p = ent
+                .map_err(|e| format!("Failed to read dir entry in {}: {e}", path.display()))?
+                .path();
+            if p.is_file() && is_sol(&p) {
+                paths.push(p);
+            }
+        }
+        paths.sort();
+        let mut out = Vec::new();
+        for p in &paths {
+            out.extend(load_known_solutions(p)?);
+        }
+        return Ok(out);
+    }
+    if is_sol(path) {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("solution");
+        return Ok(vec![parse_mip_solution(name, &read(path)?)?]);
+    }
+    parse_best_known(&read(path)?).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+// Proven values become `known_opt`, the others tighten `best_known`.
+// Names match as written or in the form `export-mip` gives them. Returns how many instances got
+// a value.
+pub fn apply_known_solutions(instances: &mut [Instance], known: &[KnownSolution]) -> usize {
+    let mut matched = 0;
+    for inst in instances.iter_mut() {
+        let names = [inst.name.trim().to_string(), sanitize(&inst.name)];
+        let mut hit = false;
+        for k in known.iter().filter(|k| names.contains(&k.name)) {
+            hit = true;
+            if k.proven {
+                inst.known_opt.get_or_insert(k.bins);
+            } else {
+                inst.best_known = Some(inst.best_known.map_or(k.bins, |b| b.min(k.bins)));
+            }
+        }
+        matched += hit as usize;
+    }
+    matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::example_instance_tp2;
+
+    #[test]
+    fn reads_solver_outputs_and_best_known_lists() {
+        let cplex = r#"<?xml version = "1.0" encoding="UTF-8" standalone="yes"?>
+<CPLEXSolution version="1.2">
+ <header
+   problemName="u250_00.lp"
+   objectiveValue="99"
+   solutionStatusString="integer optimal solution"/>
+</CPLEXSolution>"#;
+        let gurobi = "# Solution for model u250_00\n# Objective value = 1.0100000000000000e+02\nz 101\n";
+        let cbc = "Stopped on time - objective value 100.00000000\n      0 z  100  1\n";
+        let scip = "solution status: optimal solution found\nobjective value:                   99\nz 99 (obj:1)\n";
+        let highs = "Model status\nOptimal\n\n# Primal solution values\nFeasible\nObjective 99\n";
+
+        assert_eq!(parse_mip_solution("a", cplex).unwrap().bins, 99);
+        assert!(parse_mip_solution("a", cplex).unwrap().proven);
+        let g = parse_mip_solution("a", gurobi).unwrap();
+        assert_eq!((g.bins, g.proven), (101, false));
+        let c = parse_mip_solution("a", cbc).unwrap();
+        assert_eq!((c.bins, c.proven), (100, false));
+        let s = parse_mip_solution("a", scip).unwrap();
+        assert_eq!((s.bins, s.proven), (99, true));
+        let h = parse_mip_solution("a", highs).unwrap();
+        assert_eq!((h.bins, h.proven), (99, true));
+
+        let known = parse_best_known("# name bins proven\nTP2-example 4 1\nother 7 0\n").unwrap();
+        let mut insts = vec![example_instance_tp2(), example_instance_tp2()];
+        insts[0].opt_bins = None;
+        insts[1].name = "other".to_string();
+        assert_eq!(apply_known_solutions(&mut insts, &known), 2);
+        assert_eq!((insts[0].opt_bins, insts[0].known_opt), (None, Some(4)));
+        assert_eq!(insts[1].best_known, Some(7));
+        assert!(parse_best_known("x 3\n").is_err());
+    }
+}
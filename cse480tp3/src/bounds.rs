@@ -0,0 +1,266 @@
+use crate::instances::Instance;
+use crate::packing::lower_bound_bins;
+
+// Largest k used by the Fekete-Schepers functions; larger k rarely improves the bound.
+const FS_MAX_K: u32 = 100;
+
+fn sorted_sizes(instance: &Instance) -> Vec<u32> {
+    let mut sizes = instance.sizes.clone();
+    sizes.sort_unstable();
+    sizes
+}
+
+// Martello-Toth L2 on sizes sorted in increasing order. For every threshold K, items larger
+// than C - K need a bin each, items in (C/2, C - K] too, and items in [K, C/2] can only use
+// the room left by the latter.
+pub(crate) fn l2_sorted(sizes: &[u32], capacity: u32) -> usize {
+    let c = capacity as u64;
+    let mut prefix = Vec::with_capacity(sizes.len() + 1);
+    prefix.push(0u64);
+    for &s in sizes {
+        prefix.push(prefix.last().unwrap() + s as u64);
+    }
+    let half = sizes.partition_point(|&s| 2 * (s as u64) <= c);
+
+    let mut best = prefix[sizes.len()].div_ceil(c) as usize;
+    let mut thresholds: Vec<u32> = vec![0];
+    thresholds.extend(sizes[..half].iter().copied());
+    thresholds.dedup();
+
+    for k in thresholds {
+        let big = sizes.partition_point(|&s| s <= capacity - k);
+        let n1 = sizes.len() - big;
+        let n2 = big.saturating_sub(half);
+        let sum2 = prefix[big.max(half)] - prefix[half];
+        let small = sizes.partition_point(|&s| s < k);
+        let sum3 = prefix[half] - prefix[small.min(half)];
+
+        let room = (n2 as u64) * c - sum2;
+        let extra = sum3.saturating_sub(room).div_ceil(c) as usize;
+        best = best.max(n1 + n2 + extra);
+    }
+    best
+}
+
+pub fn lower_bound_l2(instance: &Instance) -> usize {
+    l2_sorted(&sorted_sizes(instance), instance.capacity)
+}
+
+// Martello-Toth reduction (MTRP). `items` must be sorted by decreasing size. Each item j, from
+// the largest, is fixed in a bin together with a set of at most two other items when that set
+// dominates every other feasible set containing j, which keeps an optimal packing reachable.
+// Returns the fixed bins and the remaining items, still in decreasing order.
+pub fn mtrp_reduction(instance: &Instance, items: &[usize]) -> (Vec<Vec<usize>>, Vec<usize>) {
+    let size = |i: usize| instance.sizes[i];
+    let mut free: Vec<usize> = items.to_vec();
+    let mut fixed: Vec<Vec<usize>> = Vec::new();
+
+    for &j in items {
+        let room = instance.capacity - size(j);
+
+        // Cheap exit: if three partners fit, no set of at most two items can dominate.
+        let smallest: Vec<u32> = free.iter().rev().filter(|&&i| i != j).take(3).map(|&i| size(i)).collect();
+        if smallest.len() == 3 && smallest.iter().sum::<u32>() <= room {
+            continue;
+        }
+
+        let first = free.partition_point(|&i| size(i) > size(j));
+        let Some(pos) = free[first..]
+            .iter()
+            .take_while(|&&i| size(i) == size(j))
+            .position(|&i| i == j)
+            .map(|p| first + p)
+        else {
+            continue;
+        };
+        free.remove(pos);
+        let m = free.len();
+        let star = free.partition_point(|&i| size(i) > room);
+        if star == m {
+            fixed.push(vec![j]);
+            continue;
+        }
+        let s_star = size(free[star]);
+        let two_fit = m >= 2 && size(free[m - 1]) + size(free[m - 2]) <= room;
+        if !two_fit || s_star == room {
+            fixed.push(vec![j, free.remove(star)]);
+            continue;
+        }
+
+        // At most two partners fit. Largest pair sum, by two pointers over the decreasing list
+        // unless the two smallest items already beat j*.
+        let (mut lo, mut hi) = (star, m - 1);
+        let mut pair_sum = size(free[m - 1]) + size(free[m - 2]);
+        while pair_sum <= s_star && lo < hi {
+            let sum = size(free[lo]) + size(free[hi]);
+            if sum > room {
+                lo += 1;
+            } else {
+                pair_sum = pair_sum.max(sum);
+                hi -= 1;
+            }
+        }
+        if s_star >= pair_sum {
+            fixed.push(vec![j, free.remove(star)]);
+            continue;
+        }
+
+        // {j, j*, b} with b the largest item fitting next to j*. It dominates every pair
+        // whose smaller item is at most b; the largest such smaller item comes from two
+        // neighbours in the decreasing list.
+        let rest = room - s_star;
+        let b = star + 1 + free[star + 1..].partition_point(|&i| size(i) > rest);
+        let (mut lo, mut hi) = (1, m);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            if size(free[mid - 1]) + size(free[mid]) > room {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        let worst_small = lo;
+        if b < m && worst_small < m && size(free[worst_small]) <= size(free[b]) {
+            let ib = free.remove(b);
+            let istar = free.remove(star);
+            fixed.push(vec![j, istar, ib]);
+            continue;
+        }
+
+        free.insert(pos, j);
+    }
+    (fixed, free)
+}
+
+// Martello-Toth L3: alternate MTRP and L2, relaxing the instance by dropping its smallest item
+// whenever the reduction is stuck.
+pub fn lower_bound_l3(instance: &Instance) -> usize {
+    let mut items: Vec<usize> = (0..instance.sizes.len()).collect();
+    items.sort_by_key(|&i| std::cmp::Reverse(instance.sizes[i]));
+
+    let mut fixed_bins = 0;
+    let mut best = 0;
+    loop {
+        let (fixed, rest) = mtrp_reduction(instance, &items);
+        fixed_bins += fixed.len();
+        let mut sizes: Vec<u32> = rest.iter().rev().map(|&i| instance.sizes[i]).collect();
+        sizes.sort_unstable();
+        best = best.max(fixed_bins + l2_sorted(&sizes, instance.capacity));
+        if rest.is_empty() {
+            return best;
+        }
+        items = rest;
+        items.pop();
+    }
+}
+
+// Fekete-Schepers bound: the best ceil(sum u(s_i)) over the dual feasible functions
+// u = u^(k) . U^(eps), where U^(eps) rounds items above C - eps up to C and drops those below
+// eps, and u^(k)(x) = x if (k + 1)x/C is integral, floor((k + 1)x/C) / k otherwise.
+// Values are kept scaled by k * C to stay in integers.
+pub fn fekete_schepers_bound(instance: &Instance) -> usize {
+    let c = instance.capacity as u64;
+    let sizes = sorted_sizes(instance);
+    let mut distinct: Vec<(u64, u64)> = Vec::new();
+    for &s in &sizes {
+        match distinct.last_mut() {
+            Some((v, n)) if *v == s as u64 => *n += 1,
+            _ => distinct.push((s as u64, 1)),
+        }
+    }
+
+    let mut epsilons: Vec<u64> = vec![0];
+    epsilons.extend(distinct.iter().map(|&(v, _)| v).filter(|&v| 2 * v <= c));
+
+    let mut best = lower_bound_bins(instance);
+    for k in 1..=FS_MAX_K.min(instance.capacity / 2).max(1) as u64 {
+        for &eps in &epsilons {
+            let total: u64 = distinct
+                .iter()
+                .map(|&(x, n)| {
+                    let y = if x > c - eps {
+                        c
+                    } else if x < eps {
+                        0
+                    } else {
+                        x
+                    };
+                    let u = if ((k + 1) * y) % c == 0 {
+                        k * y
+                    } else {
+                        ((k + 1) * y / c) * c
+                    };
+                    u * n
+                })
+                .sum();
+            best = best.max(total.div_ceil(k * c) as usize);
+        }
+    }
+    best
+}
+
+// Best of the continuous bound, L2, L3 and the Fekete-Schepers bound.
+pub fn best_lower_bound(instance: &Instance) -> usize {
+    if instance.sizes.is_empty() {
+        return 0;
+    }
+    lower_bound_bins(instance)
+        .max(lower_bound_l2(instance))
+        .max(lower_bound_l3(instance))
+        .max(fekete_schepers_bound(instance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::packing::exact_min_bins;
+
+    #[test]
+    fn bounds_never_exceed_the_optimum() {
+        let mut instances = vec![example_instance_tp2()];
+        for seed in 0..20 {
+            instances.push(synthetic_instance("bounds", 14, 100, 10, 70, seed));
+        }
+        for inst in &instances {
+            let opt = exact_min_bins(inst).unwrap();
+            assert!(lower_bound_l2(inst) <= opt, "{}: L2", inst.name);
+            assert!(lower_bound_l3(inst) <= opt, "{}: L3", inst.name);
+            assert!(fekete_schepers_bound(inst) <= opt, "{}: FS", inst.name);
+            assert!(best_lower_bound(inst) >= lower_bound_bins(inst));
+        }
+    }
+
+    #[test]
+    fn l2_beats_the_continuous_bound_on_large_items() {
+        // Six items just above C/2 need six bins, while their total only asks for four.
+        let inst = Instance {
+            name: "halves".to_string(),
+            capacity: 100,
+            sizes: vec![51; 6],
+            opt_bins: None,
+            best_known: None,
+            known_opt: None,
+        };
+        assert_eq!(lower_bound_bins(&inst), 4);
+        assert_eq!(lower_bound_l2(&inst), 6);
+        assert_eq!(best_lower_bound(&inst), 6);
+    }
+
+    #[test]
+    fn mtrp_fixes_items_that_fit_with_nothing() {
+        let inst = Instance {
+            name: "mtrp".to_string(),
+            capacity: 100,
+            sizes: vec![90, 80, 20, 15, 5],
+            opt_bins: None,
+            best_known: None,
+            known_opt: None,
+        };
+        let (fixed, rest) = mtrp_reduction(&inst, &[0, 1, 2, 3, 4]);
+        let fixed_items: usize = fixed.iter().map(|b| b.len()).sum();
+        assert_eq!(fixed_items + rest.len(), 5);
+        assert!(fixed.iter().all(|b| b.iter().map(|&i| inst.sizes[i]).sum::<u32>() <= 100));
+        assert_eq!(fixed.len(), 3);
+    }
+}
@@ -0,0 +1,299 @@
+use std::time::{Duration, Instant};
+
+use crate::instances::Instance;
+
+const EPS: f64 = 1e-9;
+
+// Knapsack tables larger than this (split items x capacity) are not attempted.
+const MAX_DP_CELLS: usize = 100_000_000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct ColgenLimits {
+    // One iteration is one pricing round plus one simplex pivot.
+    pub max_iters: u32,
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for ColgenLimits {
+    fn default() -> Self {
+        Self {
+            max_iters: 20_000,
+            time_limit: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ColgenResult {
+    // Master LP value when generation stopped; the LP optimum if `converged`.
+    pub lp_value: f64,
+    // ceil of the LP optimum, or of Farley's bound z / max pricing value if not converged.
+    pub lower_bound: usize,
+    pub converged: bool,
+    pub iters: u32,
+    pub elapsed: Duration,
+}
+
+// Restricted master of the Gilmore-Gomory model, min sum x_p s.t. sum_p a_ip x_p = d_i, kept as
+// a dense basis inverse. Every basis column is a pattern (items of each size in one bin).
+struct Master {
+    demand: Vec<f64>,
+    basis: Vec<Vec<u32>>,
+    binv: Vec<Vec<f64>>,
+    x: Vec<f64>,
+}
+
+impl Master {
+    // Starts from the homogeneous patterns, whose basis is diagonal.
+    fn new(sizes: &[u32], demand: &[u32], capacity: u32) -> Self {
+        let m = sizes.len();
+        let mut basis = Vec::with_capacity(m);
+        let mut binv = vec![vec![0.0; m]; m];
+        let mut x = vec![0.0; m];
+        for i in 0..m {
+            let k = (capacity / sizes[i]).min(demand[i]).max(1);
+            let mut col = vec![0; m];
+            col[i] = k;
+            basis.push(col);
+            binv[i][i] = 1.0 / k as f64;
+            x[i] = demand[i] as f64 / k as f64;
+        }
+        Self {
+            demand: demand.iter().map(|&d| d as f64).collect(),
+            basis,
+            binv,
+            x,
+        }
+    }
+
+    fn value(&self) -> f64 {
+        self.x.iter().sum()
+    }
+
+    // y = c_B B^-1 with unit costs: the column sums of B^-1.
+    fn duals(&self) -> Vec<f64> {
+        let m = self.x.len();
+        (0..m).map(|j| (0..m).map(|i| self.binv[i][j]).sum()).collect()
+    }
+
+    // Enters `col`, leaving the basic variable chosen by the ratio test (lowest row on ties).
+    fn pivot(&mut self, col: Vec<u32>) -> bool {
+        let m = self.x.len();
+        let dir: Vec<f64> = (0..m)
+            .map(|i| (0..m).map(|j| self.binv[i][j] * col[j] as f64).sum())
+            .collect();
+        let mut leave: Option<(usize, f64)> = None;
+        for (i, (&d, &x)) in dir.iter().zip(self.x.iter()).enumerate() {
+            if d > EPS {
+                let ratio = x / d;
+                if leave.is_none_or(|(_, r)| ratio < r - EPS) {
+                    leave = Some((i, ratio));
+                }
+            }
+        }
+        let Some((r, theta)) = leave else {
+            return false;
+        };
+
+        for (x, &d) in self.x.iter_mut().zip(dir.iter()) {
+            *x -= theta * d;
+        }
+        self.x[r] = theta;
+        let pivot_row: Vec<f64> = self.binv[r].iter().map(|v| v / dir[r]).collect();
+        for (i, row) in self.binv.iter_mut().enumerate() {
+            if i != r && dir[i] != 0.0 {
+                for (v, &p) in row.iter_mut().zip(pivot_row.iter()) {
+                    *v -= dir[i] * p;
+                }
+            }
+        }
+        self.binv[r] = pivot_row;
+        self.basis[r] = col;
+        true
+    }
+
+    // Recomputes B^-1 and x from the basis columns by Gauss-Jordan, against accumulated error.
+    fn refactor(&mut self) {
+        let m = self.x.len();
+        let mut a: Vec<Vec<f64>> = (0..m)
+            .map(|i| (0..m).map(|j| self.basis[j][i] as f64).collect())
+            .collect();
+        let mut inv: Vec<Vec<f64>> = (0..m)
+            .map(|i| (0..m).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+            .collect();
+        for c in 0..m {
+            let p = (c..m)
+                .max_by(|&u, &v| a[u][c].abs().partial_cmp(&a[v][c].abs()).unwrap())
+                .unwrap();
+            if a[p][c].abs() < EPS {
+                return;
+            }
+            a.swap(c, p);
+            inv.swap(c, p);
+            let d = a[c][c];
+            for j in 0..m {
+                a[c][j] /= d;
+                inv[c][j] /= d;
+            }
+            for i in 0..m {
+                let f = a[i][c];
+                if i != c && f != 0.0 {
+                    for j in 0..m {
+                        a[i][j] -= f * a[c][j];
+                        inv[i][j] -= f * inv[c][j];
+                    }
+                }
+            }
+        }
+        self.x = (0..m)
+            .map(|i| (0..m).map(|j| inv[i][j] * self.demand[j]).sum::<f64>().max(0.0))
+            .collect();
+        self.binv = inv;
+    }
+}
+
+// Bounded knapsack by DP over binary-split copies: the pattern of largest dual value that fits.
+// `keep` is scratch space, reused across pricing rounds since it can grow to MAX_DP_CELLS.
+fn price(sizes: &[u32], demand: &[u32], duals: &[f64], capacity: u32, keep: &mut Vec<bool>) -> Option<(f64, Vec<u32>)> {
+    let cap = capacity as usize;
+    let mut parts: Vec<(usize, u32)> = Vec::new();
+    for i in 0..sizes.len() {
+        if duals[i] <= EPS {
+            continue;
+        }
+        let mut left = demand[i].min(capacity / sizes[i]);
+        let mut k = 1;
+        while left > 0 {
+            let take = k.min(left);
+            parts.push((i, take));
+            left -= take;
+            k *= 2;
+        }
+    }
+    if parts.len().saturating_mul(cap + 1) > MAX_DP_CELLS {
+        return None;
+    }
+
+    let mut best = vec![0.0f64; cap + 1];
+    keep.clear();
+    keep.resize(parts.len() * (cap + 1), false);
+    for (p, &(i, take)) in parts.iter().enumerate() {
+        let w = (sizes[i] * take) as usize;
+        let v = duals[i] * take as f64;
+        for c in (w..=cap).rev() {
+            if best[c - w] + v > best[c] + EPS {
+                best[c] = best[c - w] + v;
+                keep[p * (cap + 1) + c] = true;
+            }
+        }
+    }
+
+    let mut pattern = vec![0; sizes.len()];
+    let mut c = cap;
+    for (p, &(i, take)) in parts.iter().enumerate().rev() {
+        if keep[p * (cap + 1) + c] {
+            pattern[i] += take;
+            c -= (sizes[i] * take) as usize;
+        }
+    }
+    Some((best[cap], pattern))
+}
+
+// Gilmore-Gomory LP relaxation by column generation. None when the instance is empty, has an
+// item larger than the capacity, or the pricing table would be too large.
+pub fn column_generation_bound(instance: &Instance, limits: ColgenLimits) -> Option<ColgenResult> {
+    let start = Instant::now();
+    if instance.sizes.is_empty() || instance.sizes.iter().any(|&s| s == 0 || s > instance.capacity) {
+        return None;
+    }
+
+    let mut sorted = instance.sizes.clone();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    let mut sizes: Vec<u32> = Vec::new();
+    let mut demand: Vec<u32> = Vec::new();
+    for s in sorted {
+        if sizes.last() == Some(&s) {
+            *demand.last_mut().unwrap() += 1;
+        } else {
+            sizes.push(s);
+            demand.push(1);
+        }
+    }
+
+    let mut master = Master::new(&sizes, &demand, instance.capacity);
+    let mut keep: Vec<bool> = Vec::new();
+    let mut lower_bound = 0;
+    let mut converged = false;
+    let mut iters = 0;
+
+    while iters < limits.max_iters {
+        if let Some(limit) = limits.time_limit {
+            if start.elapsed() >= limit {
+                break;
+            }
+        }
+        iters += 1;
+
+        let z = master.value();
+        let (best, pattern) = price(&sizes, &demand, &master.duals(), instance.capacity, &mut keep)?;
+        lower_bound = lower_bound.max((z / best.max(1.0) - 1e-6).ceil() as usize);
+        if best <= 1.0 + 1e-7 {
+            converged = true;
+            break;
+        }
+        if !master.pivot(pattern) {
+            break;
+        }
+        if iters % 100 == 0 {
+            master.refactor();
+        }
+    }
+
+    Some(ColgenResult {
+        lp_value: master.value(),
+        lower_bound,
+        converged,
+        iters,
+        elapsed: start.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bounds::best_lower_bound;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::packing::exact_min_bins;
+
+    #[test]
+    fn lp_bound_lies_between_the_simple_bounds_and_the_optimum() {
+        let mut instances = vec![example_instance_tp2()];
+        for seed in 0..20 {
+            instances.push(synthetic_instance("colgen", 14, 100, 10, 70, seed));
+        }
+        for inst in &instances {
+            let res = column_generation_bound(inst, ColgenLimits::default()).unwrap();
+            assert!(res.converged, "{}", inst.name);
+            let opt = exact_min_bins(inst).unwrap();
+            assert!(res.lower_bound <= opt, "{}", inst.name);
+            assert!(res.lp_value + 1e-6 >= inst.sizes.iter().sum::<u32>() as f64 / inst.capacity as f64);
+        }
+    }
+
+    #[test]
+    fn three_halves_need_one_and_a_half_bins() {
+        let inst = Instance {
+            name: "halves".to_string(),
+            capacity: 100,
+            sizes: vec![50, 50, 50, 60],
+            opt_bins: None,
+            best_known: None,
+            known_opt: None,
+        };
+        // The 60 takes a bin of its own; three 50s fill one and a half more.
+        let res = column_generation_bound(&inst, ColgenLimits::default()).unwrap();
+        assert!((res.lp_value - 2.5).abs() < 1e-6);
+        assert_eq!(res.lower_bound, 3);
+        assert!(res.lower_bound >= best_lower_bound(&inst));
+    }
+}
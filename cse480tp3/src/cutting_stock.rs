@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+use std::io::Write;
+
+use crate::instances::Instance;
+use crate::packing::Packing;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ItemType {
+    pub size: u32,
+    pub demand: usize,
+}
+
+// A cutting-stock instance: piece lengths with the quantity needed of each.
+#[derive(Clone, Debug)]
+pub struct CuttingStock {
+    pub name: String,
+    pub capacity: u32,
+    pub types: Vec<ItemType>,
+}
+
+impl CuttingStock {
+    pub fn n_items(&self) -> usize {
+        self.types.iter().map(|t| t.demand).sum()
+    }
+
+    // The bin packing view the solvers work on: one item per piece, the pieces of a type next
+    // to each other. The searches treat equal sizes as interchangeable.
+    pub fn to_instance(&self) -> Instance {
+        let mut sizes = Vec::with_capacity(self.n_items());
+        for t in &self.types {
+            sizes.extend(std::iter::repeat_n(t.size, t.demand));
+        }
+        Instance {
+            name: self.name.clone(),
+            capacity: self.capacity,
+            sizes,
+            opt_bins: None,
+            best_known: None,
+            known_opt: None,
+        }
+    }
+
+    // Merges equal sizes into one type each, largest first.
+    pub fn from_instance(instance: &Instance) -> Self {
+        let mut demand: HashMap<u32, usize> = HashMap::new();
+        for &size in &instance.sizes {
+            *demand.entry(size).or_insert(0) += 1;
+        }
+        let mut types: Vec<ItemType> = demand.into_iter().map(|(size, demand)| ItemType { size, demand }).collect();
+        types.sort_by_key(|t| std::cmp::Reverse(t.size));
+        Self {
+            name: instance.name.clone(),
+            capacity: instance.capacity,
+            types,
+        }
+    }
+}
+
+// BPPLIB cutting-stock layout: the number of item types m, the capacity, then m lines of
+// `size demand`. Blank lines and `#` comments are skipped.
+pub fn parse_cutting_stock(name: &str, content: &str) -> Result<CuttingStock, String> {
+    let lines: Vec<Vec<&str>> = content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|l| l.split_whitespace().collect())
+        .collect();
+    if lines.len() < 2 || lines[0].len() != 1 || lines[1].len() != 1 {
+        return Err("expected the number of item types and the capacity on their own lines".to_string());
+    }
+    let m: usize = lines[0][0]
+        .parse()
+        .map_err(|_| format!("invalid number of item types `{}`", lines[0][0]))?;
+    let capacity: u32 = lines[1][0]
+        .parse()
+        .map_err(|_| format!("invalid capacity `{}`", lines[1][0]))?;
+    if capacity == 0 {
+        return Err("capacity must be > 0".to_string());
+    }
+    if lines.len() != 2 + m {
+        return Err(format!("expected {m} `size demand` lines, found {}", lines.len() - 2));
+    }
+
+    let mut types = Vec::with_capacity(m);
+    for (k, toks) in lines[2..].iter().enumerate() {
+        let [size, demand] = toks[..] else {
+            return Err(format!("item type {}: expected `size demand`", k + 1));
+        };
+        let size: u32 = size
+            .parse()
+            .map_err(|_| format!("item type {}: invalid size `{size}`", k + 1))?;
+        let demand: usize = demand
+            .parse()
+            .map_err(|_| format!("item type {}: invalid demand `{demand}`", k + 1))?;
+        if size == 0 || size > capacity {
+            return Err(format!("item type {}: size {size} outside 1..={capacity}", k + 1));
+        }
+        types.push(ItemType { size, demand });
+    }
+    Ok(CuttingStock {
+        name: name.to_string(),
+        capacity,
+        types,
+    })
+}
+
+// One way of cutting a bin and how many bins are cut that way.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CuttingPattern {
+    // (size, pieces), largest size first.
+    pub pieces: Vec<(u32, usize)>,
+    pub load: u32,
+    pub count: usize,
+}
+
+// Groups the bins of `packing` by the sizes they hold, ignoring which of the identical pieces
+// went where. The most used patterns come first.
+pub fn cutting_patterns(instance: &Instance, packing: &Packing) -> Vec<CuttingPattern> {
+    let mut patterns: Vec<CuttingPattern> = Vec::new();
+    for (bin, &load) in packing.bins.iter().zip(packing.bin_loads.iter()) {
+        let mut sizes: Vec<u32> = bin.iter().map(|&i| instance.sizes[i]).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        let mut pieces: Vec<(u32, usize)> = Vec::new();
+        for size in sizes {
+            match pieces.last_mut() {
+                Some((s, k)) if *s == size => *k += 1,
+                _ => pieces.push((size, 1)),
+            }
+        }
+        match patterns.iter_mut().find(|p| p.pieces == pieces) {
+            Some(p) => p.count += 1,
+            None => patterns.push(CuttingPattern { pieces, load, count: 1 }),
+        }
+    }
+    patterns.sort_by(|a, b| b.count.cmp(&a.count).then(b.load.cmp(&a.load)));
+    patterns
+}
+
+// `pattern <bins> <load>: <size>*<pieces> ...`, one line per pattern.
+pub fn write_patterns<W: Write>(
+    instance: &Instance,
+    patterns: &[CuttingPattern],
+    out: &mut W,
+) -> std::io::Result<()> {
+    writeln!(out, "# cse480tp3 cutting patterns")?;
+    writeln!(out, "instance {}", instance.name.trim())?;
+    writeln!(out, "capacity {}", instance.capacity)?;
+    writeln!(out, "bins {}", patterns.iter().map(|p| p.count).sum::<usize>())?;
+    writeln!(out, "patterns {}", patterns.len())?;
+    for p in patterns {
+        let pieces = p
+            .pieces
+            .iter()
+            .map(|(size, k)| format!("{size}*{k}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(out, "pattern {} {}: {pieces}", p.count, p.load)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packing::validate_packing;
+    use crate::solver::Solver;
+    use crate::tabu::TabuParams;
+
+    #[test]
+    fn demands_expand_to_items_and_packings_group_into_patterns() {
+        let cs = parse_cutting_stock("roll", "# pieces\n3\n100\n45 6\n30 4\n25 8\n").unwrap();
+        assert_eq!(cs.n_items(), 18);
+        let inst = cs.to_instance();
+        assert_eq!(&inst.sizes[5..8], &[45, 30, 30]);
+        assert_eq!(CuttingStock::from_instance(&inst).types, cs.types);
+        assert!(parse_cutting_stock("x", "2\n100\n45 6\n").is_err());
+        assert!(parse_cutting_stock("x", "1\n100\n120 1\n").is_err());
+
+        let params = TabuParams {
+            max_iters: 300,
+            ..TabuParams::default()
+        };
+        let res = params.solve(&inst, 0);
+        validate_packing(&inst, &res.best_packing).unwrap();
+        let patterns = cutting_patterns(&inst, &res.best_packing);
+        assert_eq!(patterns.iter().map(|p| p.count).sum::<usize>(), res.best_bins);
+        for t in &cs.types {
+            let cut: usize = patterns
+                .iter()
+                .flat_map(|p| p.pieces.iter().filter(|(s, _)| *s == t.size).map(move |(_, k)| k * p.count))
+                .sum();
+            assert_eq!(cut, t.demand);
+        }
+        assert!(patterns.len() < res.best_bins);
+
+        let mut buf = Vec::new();
+        write_patterns(&inst, &patterns, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert!(text.contains(&format!("bins {}\n", res.best_bins)));
+        assert!(text.lines().any(|l| l.starts_with("pattern ") && l.contains("*")));
+    }
+}
@@ -0,0 +1,327 @@
+use std::time::{Duration, Instant};
+
+use crate::bounds::{best_lower_bound, l2_sorted};
+use crate::instances::Instance;
+use crate::packing::{try_reduce_bins, BestFit, Decoder, Packing};
+
+#[derive(Clone, Copy, Debug)]
+pub struct ExactLimits {
+    // Search nodes plus enumerated bin completions.
+    pub max_nodes: u64,
+    pub time_limit: Option<Duration>,
+}
+
+impl Default for ExactLimits {
+    fn default() -> Self {
+        Self {
+            max_nodes: 2_000_000,
+            time_limit: Some(Duration::from_secs(10)),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExactResult {
+    // True when `best` is optimal, false when the budget ran out first.
+    pub proven: bool,
+    pub lower_bound: usize,
+    pub best: Packing,
+    pub nodes: u64,
+    pub elapsed: Duration,
+}
+
+// After every packing with a bin {x} + `items` has been explored, a sibling branch never needs
+// a bin holding `items` plus at most `size` = s(x) more: swapping those items with x would give
+// a packing that was already searched. Items are indices into `Search::sizes`.
+struct Nogood {
+    items: Vec<usize>,
+    size: u32,
+}
+
+// Korf's bin completion on item sizes: the largest unpacked item opens a bin, and the branches
+// are the undominated ways of completing that bin, fullest first.
+struct Search {
+    capacity: u32,
+    // Distinct sizes in decreasing order, and how many items of each are still unpacked.
+    sizes: Vec<u32>,
+    counts: Vec<usize>,
+    bins: Vec<Vec<usize>>,
+    best: Vec<Vec<usize>>,
+    lower_bound: usize,
+    limits: ExactLimits,
+    start: Instant,
+    nodes: u64,
+    aborted: bool,
+}
+
+impl Search {
+    fn out_of_budget(&mut self) -> bool {
+        if !self.aborted && self.nodes >= self.limits.max_nodes {
+            self.aborted = true;
+        }
+        if !self.aborted && self.nodes.is_multiple_of(1024) {
+            if let Some(limit) = self.limits.time_limit {
+                self.aborted = self.start.elapsed() >= limit;
+            }
+        }
+        self.aborted
+    }
+
+    fn done(&self) -> bool {
+        self.aborted || self.best.len() == self.lower_bound
+    }
+
+    fn remaining_l2(&self) -> usize {
+        let mut rest = Vec::new();
+        for (k, &s) in self.sizes.iter().enumerate().rev() {
+            rest.extend(std::iter::repeat_n(s, self.counts[k]));
+        }
+        l2_sorted(&rest, self.capacity)
+    }
+
+    fn search(&mut self, nogoods: &mut Vec<Nogood>) {
+        self.nodes += 1;
+        if self.out_of_budget() {
+            return;
+        }
+        let Some(k) = self.counts.iter().position(|&c| c > 0) else {
+            if self.bins.len() < self.best.len() {
+                self.best = self.bins.clone();
+            }
+            return;
+        };
+        if self.bins.len() + self.remaining_l2() >= self.best.len() {
+            return;
+        }
+
+        let x = self.sizes[k];
+        self.counts[k] -= 1;
+        let completions = self.completions(self.capacity - x);
+        let inherited = nogoods.len();
+
+        for comp in completions {
+            if self.done() {
+                break;
+            }
+            if nogoods.iter().any(|ng| self.rejects(ng, k, &comp)) {
+                continue;
+            }
+            for &j in &comp {
+                self.counts[j] -= 1;
+            }
+            let mut bin = vec![k];
+            bin.extend(comp.iter().copied());
+            self.bins.push(bin);
+
+            self.search(nogoods);
+
+            self.bins.pop();
+            for &j in &comp {
+                self.counts[j] += 1;
+            }
+            nogoods.push(Nogood { items: comp, size: x });
+        }
+
+        nogoods.truncate(inherited);
+        self.counts[k] += 1;
+    }
+
+    fn rejects(&self, nogood: &Nogood, k: usize, comp: &[usize]) -> bool {
+        // Both lists are sorted by index, so containment is a merge.
+        let mut bin = Vec::with_capacity(comp.len() + 1);
+        bin.push(k);
+        bin.extend_from_slice(comp);
+        let mut rest: u32 = bin.iter().map(|&j| self.sizes[j]).sum();
+        let mut b = 0;
+        for &j in &nogood.items {
+            while b < bin.len() && bin[b] < j {
+                b += 1;
+            }
+            if b == bin.len() || bin[b] != j {
+                return false;
+            }
+            rest -= self.sizes[j];
+            b += 1;
+        }
+        rest <= nogood.size
+    }
+
+    // Undominated completions of a bin with `residual` free space, as lists of size indices,
+    // fullest first.
+    fn completions(&mut self, residual: u32) -> Vec<Vec<usize>> {
+        let mut suffix = vec![0u64; self.sizes.len() + 1];
+        for j in (0..self.sizes.len()).rev() {
+            suffix[j] = suffix[j + 1] + (self.sizes[j] as u64) * (self.counts[j] as u64);
+        }
+        let mut out = Vec::new();
+        let mut current = Vec::new();
+        self.enumerate(0, residual, u32::MAX, &suffix, &mut current, &mut out);
+        out.sort_by_key(|c: &Vec<usize>| std::cmp::Reverse(c.iter().map(|&j| self.sizes[j]).sum::<u32>()));
+        out
+    }
+
+    // `min_excluded` is the smallest size skipped while items of it were still available: the
+    // completion is only maximal if the final free space ends up below it.
+    fn enumerate(
+        &mut self,
+        j: usize,
+        residual: u32,
+        min_excluded: u32,
+        suffix: &[u64],
+        current: &mut Vec<usize>,
+        out: &mut Vec<Vec<usize>>,
+    ) {
+        if self.aborted || (residual as u64).saturating_sub(suffix[j]) >= min_excluded as u64 {
+            return;
+        }
+        if j == self.sizes.len() {
+            self.nodes += 1;
+            if !self.out_of_budget() && !self.dominated(current, residual) {
+                out.push(current.clone());
+            }
+            return;
+        }
+
+        let s = self.sizes[j];
+        let max_take = self.counts[j].min((residual / s) as usize);
+        for take in (0..=max_take).rev() {
+            current.extend(std::iter::repeat_n(j, take));
+            let excluded = if take < self.counts[j] { min_excluded.min(s) } else { min_excluded };
+            self.enumerate(j + 1, residual - (take as u32) * s, excluded, suffix, current, out);
+            current.truncate(current.len() - take);
+        }
+    }
+
+    // A completion is dominated when a left-out item can replace one or two of its items whose
+    // sizes add up to at most its own and still fit.
+    fn dominated(&self, comp: &[usize], residual: u32) -> bool {
+        for (e, &es) in self.sizes.iter().enumerate() {
+            let used = comp.iter().filter(|&&j| j == e).count();
+            if used == self.counts[e] {
+                continue;
+            }
+            for (p, &a) in comp.iter().enumerate() {
+                let sa = self.sizes[a];
+                if sa < es && es - sa <= residual {
+                    return true;
+                }
+                for &b in &comp[p + 1..] {
+                    let pair = sa + self.sizes[b];
+                    if pair <= es && es - pair <= residual {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+// Exact bin packing by bin completion with the L2 bound at every node and nogood pruning,
+// starting from best fit decreasing. Stops at `limits`; `proven` tells whether it finished.
+pub fn bin_completion(instance: &Instance, limits: ExactLimits) -> Result<ExactResult, String> {
+    if instance.sizes.iter().any(|&s| s > instance.capacity) {
+        return Err("Instance contains an item larger than bin capacity.".to_string());
+    }
+    let start = Instant::now();
+
+    let mut order: Vec<usize> = (0..instance.sizes.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(instance.sizes[i]));
+    let bfd = try_reduce_bins(instance, &BestFit.pack(instance, &order));
+
+    let mut sizes: Vec<u32> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    let mut by_size: Vec<Vec<usize>> = Vec::new();
+    for &i in &order {
+        let s = instance.sizes[i];
+        if sizes.last() != Some(&s) {
+            sizes.push(s);
+            counts.push(0);
+            by_size.push(Vec::new());
+        }
+        *counts.last_mut().unwrap() += 1;
+        by_size.last_mut().unwrap().push(i);
+    }
+
+    let lower_bound = best_lower_bound(instance);
+    let mut search = Search {
+        capacity: instance.capacity,
+        sizes,
+        counts,
+        bins: Vec::new(),
+        // Placeholder bins standing for the best fit packing; only their count matters.
+        best: vec![Vec::new(); bfd.n_bins()],
+        lower_bound,
+        limits,
+        start,
+        nodes: 0,
+        aborted: false,
+    };
+    if search.best.len() > lower_bound {
+        search.search(&mut Vec::new());
+    }
+
+    let proven = !search.aborted;
+    let best = if search.best.len() < bfd.n_bins() {
+        let bins: Vec<Vec<usize>> = search
+            .best
+            .iter()
+            .map(|bin| bin.iter().map(|&j| by_size[j].pop().unwrap()).collect())
+            .collect();
+        let bin_loads = bins
+            .iter()
+            .map(|b: &Vec<usize>| b.iter().map(|&i| instance.sizes[i]).sum())
+            .collect();
+        Packing {
+            capacity: instance.capacity,
+            bins,
+            bin_loads,
+        }
+    } else {
+        bfd
+    };
+
+    Ok(ExactResult {
+        proven,
+        lower_bound: if proven { best.n_bins() } else { lower_bound },
+        best,
+        nodes: search.nodes,
+        elapsed: start.elapsed(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::packing::{exact_min_bins, validate_packing};
+
+    #[test]
+    fn bin_completion_matches_plain_dfs() {
+        let mut instances = vec![example_instance_tp2()];
+        for seed in 0..30 {
+            instances.push(synthetic_instance("bc", 16, 100, 10, 70, seed));
+        }
+        for inst in &instances {
+            let res = bin_completion(inst, ExactLimits::default()).unwrap();
+            assert!(res.proven, "{}", inst.name);
+            validate_packing(inst, &res.best).unwrap();
+            assert_eq!(res.best.n_bins(), exact_min_bins(inst).unwrap(), "{}", inst.name);
+        }
+    }
+
+    #[test]
+    fn node_budget_stops_the_search_without_a_proof() {
+        let inst = synthetic_instance("bc-budget", 120, 1000, 100, 600, 1);
+        let limits = ExactLimits {
+            max_nodes: 50,
+            time_limit: None,
+        };
+        let res = bin_completion(&inst, limits).unwrap();
+        validate_packing(&inst, &res.best).unwrap();
+        assert!(res.lower_bound <= res.best.n_bins());
+        if !res.proven {
+            assert!(res.nodes >= 50);
+        }
+    }
+}
@@ -1,42 +1,124 @@
 use std::io::Write;
 
+use crate::bounds::best_lower_bound;
+use crate::colgen::{column_generation_bound, ColgenLimits};
+use crate::exact::{bin_completion, ExactLimits};
 use crate::instances::Instance;
-use crate::packing::exact_bins_if_small;
-use crate::tabu::{tabu_search, TabuParams};
+use crate::solver::Solver;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoundSource {
+    DatasetOpt,
+    KnownOptimal,
+    BinCompletion,
+    BestKnown,
+    LpBound,
+    LowerBound,
+}
+
+impl BoundSource {
+    pub fn name(self) -> &'static str {
+        match self {
+            BoundSource::DatasetOpt => "dataset-opt",
+            BoundSource::KnownOptimal => "known-opt",
+            BoundSource::BinCompletion => "bin-completion",
+            BoundSource::BestKnown => "best-known",
+            BoundSource::LpBound => "lp-bound",
+            BoundSource::LowerBound => "lower-bound",
+        }
+    }
+}
+
+// Reference bin counts for gap reporting: a lower bound, the best packing known to exist (if
+// any), and whether the two meet, i.e. the optimum is known.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct ExactRef {
-    pub bins: usize,
-    pub source: &'static str,
+    pub lower_bound: usize,
+    pub lower_source: BoundSource,
+    pub upper_bound: Option<usize>,
+    pub upper_source: Option<BoundSource>,
+    pub proven: bool,
 }
 
-fn lower_bound_bins(instance: &Instance) -> usize {
-    if instance.capacity == 0 {
-        return 0;
+impl ExactRef {
+    fn optimum(bins: usize, source: BoundSource) -> Self {
+        Self {
+            lower_bound: bins,
+            lower_source: source,
+            upper_bound: Some(bins),
+            upper_source: Some(source),
+            proven: true,
+        }
+    }
+
+    // "41" for a proven optimum, "41*" for a bound.
+    pub fn lower_label(&self) -> String {
+        format!("{}{}", self.lower_bound, if self.proven { "" } else { "*" })
+    }
+
+    pub fn upper_label(&self) -> String {
+        match self.upper_bound {
+            Some(ub) => format!("{}{}", ub, if self.proven { "" } else { "*" }),
+            None => "-".to_string(),
+        }
+    }
+
+    pub fn source_label(&self) -> String {
+        match self.upper_source {
+            Some(u) if self.proven && u == self.lower_source => u.name().to_string(),
+            Some(u) => format!("{}/{}", self.lower_source.name(), u.name()),
+            None => self.lower_source.name().to_string(),
+        }
+    }
+
+    // Gap to the lower bound: an upper bound on the true optimality gap.
+    pub fn gap_lb(&self, found: usize) -> f64 {
+        gap_percent(found, self.lower_bound)
+    }
+
+    // Gap to the best known packing; negative when `found` improves on it.
+    pub fn gap_ub(&self, found: usize) -> Option<f64> {
+        self.upper_bound.map(|ub| gap_percent(found, ub))
     }
-    let total: u64 = instance.sizes.iter().map(|&v| v as u64).sum();
-    let cap: u64 = instance.capacity as u64;
-    ((total + cap - 1) / cap) as usize
 }
 
 pub fn exact_reference(instance: &Instance) -> Option<ExactRef> {
+    exact_reference_with_limits(instance, ExactLimits::default())
+}
+
+// None only when an item does not fit in a bin.
+pub fn exact_reference_with_limits(instance: &Instance, limits: ExactLimits) -> Option<ExactRef> {
     if let Some(b) = instance.opt_bins {
-        return Some(ExactRef {
-            bins: b,
-            source: "dataset-opt",
-        });
-    }
-    // Brute force / exact is only practical for very small instances.
-    if let Some(b) = exact_bins_if_small(instance, 30) {
-        return Some(ExactRef {
-            bins: b,
-            source: "bruteforce",
-        });
-    }
-    // Fallback reference (not exact): capacity lower bound.
+        return Some(ExactRef::optimum(b, BoundSource::DatasetOpt));
+    }
+    if let Some(b) = instance.known_opt {
+        return Some(ExactRef::optimum(b, BoundSource::KnownOptimal));
+    }
+    // Bin completion under a node/time budget; without a proof its packing is still an upper bound.
+    let bc = bin_completion(instance, limits).ok()?;
+    if bc.proven {
+        return Some(ExactRef::optimum(bc.best.n_bins(), BoundSource::BinCompletion));
+    }
+    let (mut lower, mut lower_source) = (best_lower_bound(instance), BoundSource::LowerBound);
+    if let Some(cg) = column_generation_bound(instance, ColgenLimits::default()) {
+        if cg.lower_bound > lower {
+            (lower, lower_source) = (cg.lower_bound, BoundSource::LpBound);
+        }
+    }
+
+    let (mut upper, mut upper_source) = (bc.best.n_bins(), BoundSource::BinCompletion);
+    if let Some(b) = instance.best_known {
+        // A best-known value below a valid lower bound is a bad input; keep our own packing.
+        if lower <= b && b <= upper {
+            (upper, upper_source) = (b, BoundSource::BestKnown);
+        }
+    }
     Some(ExactRef {
-        bins: lower_bound_bins(instance),
-        source: "lower-bound",
+        lower_bound: lower,
+        lower_source,
+        upper_bound: Some(upper),
+        upper_source: Some(upper_source),
+        proven: lower == upper,
     })
 }
 
@@ -51,13 +133,13 @@ pub fn compare_against_exact<W: Write>(
     instance: &Instance,
     runs: u32,
     seed0: u64,
-    params: TabuParams,
+    solver: &dyn Solver,
     out: &mut W,
 ) -> std::io::Result<()> {
     let Some(exact) = exact_reference(instance) else {
         writeln!(
             out,
-            "instance={} exact=N/A (n={}, no dataset-opt and too large for brute force)",
+            "instance={} exact=N/A (n={}, an item is larger than the bin capacity)",
             instance.name,
             instance.sizes.len()
         )?;
@@ -66,20 +148,28 @@ pub fn compare_against_exact<W: Write>(
 
     writeln!(
         out,
-        "instance={} exact_bins={} exact_source={}",
-        instance.name, exact.bins, exact.source
+        "instance={} lb={} ub={} proven={} source={} solver={}",
+        instance.name,
+        exact.lower_bound,
+        exact.upper_bound.map(|v| v.to_string()).unwrap_or_else(|| "N/A".to_string()),
+        exact.proven,
+        exact.source_label(),
+        solver.name()
     )?;
     for r in 0..runs {
         let seed = seed0 + (r as u64);
-        let res = tabu_search(instance, seed, params);
-        let gap = gap_percent(res.best_bins, exact.bins);
+        let res = solver.solve(instance, seed);
         writeln!(
             out,
-            "  run={} seed={} found_bins={} gap_percent={:.2}",
+            "  run={} seed={} found_bins={} gap_lb_percent={:.2} gap_ub_percent={}",
             r + 1,
             seed,
             res.best_bins,
-            gap
+            exact.gap_lb(res.best_bins),
+            exact
+                .gap_ub(res.best_bins)
+                .map(|g| format!("{g:.2}"))
+                .unwrap_or_else(|| "N/A".to_string())
         )?;
     }
     Ok(())
@@ -89,16 +179,65 @@ pub fn gaps_against_exact(
     instance: &Instance,
     runs: u32,
     seed0: u64,
-    params: TabuParams,
+    solver: &dyn Solver,
 ) -> Option<(ExactRef, Vec<usize>, Vec<f64>)> {
     let exact = exact_reference(instance)?;
     let mut found: Vec<usize> = Vec::with_capacity(runs as usize);
     let mut gaps: Vec<f64> = Vec::with_capacity(runs as usize);
     for r in 0..runs {
         let seed = seed0 + (r as u64);
-        let res = tabu_search(instance, seed, params);
+        let res = solver.solve(instance, seed);
         found.push(res.best_bins);
-        gaps.push(gap_percent(res.best_bins, exact.bins));
+        gaps.push(exact.gap_lb(res.best_bins));
     }
     Some((exact, found, gaps))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+
+    #[test]
+    fn reference_is_proven_only_when_the_bounds_meet() {
+        let tp2 = example_instance_tp2();
+        let exact = exact_reference(&tp2).unwrap();
+        assert!(exact.proven);
+        assert_eq!((exact.lower_bound, exact.upper_bound), (4, Some(4)));
+        assert_eq!(exact.source_label(), "dataset-opt");
+        assert_eq!(exact.lower_label(), "4");
+
+        // One node is not enough for bin completion to close the gap on this instance.
+        let mut inst = synthetic_instance("unproven", 150, 1000, 200, 700, 3);
+        let limits = ExactLimits {
+            max_nodes: 1,
+            time_limit: None,
+        };
+        let bc_bins = bin_completion(&inst, limits).unwrap().best.n_bins();
+        let lower = exact_reference_with_limits(&inst, limits).unwrap().lower_bound;
+        assert!(lower < bc_bins);
+
+        // The useless best-known value loses to the bin completion packing.
+        inst.best_known = Some(inst.sizes.len());
+        let exact = exact_reference_with_limits(&inst, limits).unwrap();
+        assert!(!exact.proven);
+        assert_eq!(exact.upper_bound, Some(bc_bins));
+        assert_eq!(exact.upper_source, Some(BoundSource::BinCompletion));
+        assert!(exact.lower_label().ends_with('*'));
+        assert!(exact.upper_label().ends_with('*'));
+        assert!(exact.gap_lb(bc_bins) > exact.gap_ub(bc_bins).unwrap());
+
+        // A best-known value below the lower bound is ignored rather than clamped into a proof.
+        inst.best_known = Some(lower - 1);
+        let exact = exact_reference_with_limits(&inst, limits).unwrap();
+        assert!(!exact.proven);
+        assert_eq!(exact.upper_bound, Some(bc_bins));
+        assert_eq!(exact.upper_source, Some(BoundSource::BinCompletion));
+
+        // A usable one tightens the upper bound.
+        inst.best_known = Some(bc_bins - 1);
+        let exact = exact_reference_with_limits(&inst, limits).unwrap();
+        assert_eq!(exact.upper_source, Some(BoundSource::BestKnown));
+        assert_eq!(exact.proven, lower == bc_bins - 1);
+    }
+}
@@ -1,9 +1,43 @@
 use std::time::Duration;
 use std::io::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 use crate::instances::Instance;
-use crate::exact_compare::{exact_reference, gap_percent};
-use crate::tabu::{tabu_search, TabuParams};
+use crate::exact_compare::{exact_reference, gap_percent, ExactRef};
+use crate::solver::Solver;
+use crate::tabu::{StopReason, TabuResult};
+
+// One solver run on one instance, the unit of the CSV export.
+#[derive(Clone, Debug)]
+pub struct RunRecord {
+    pub instance_name: String,
+    pub seed: u64,
+    pub bins: usize,
+    pub unused: u32,
+    pub elapsed_s: f64,
+    pub iters: u32,
+    pub stop_reason: StopReason,
+    // Gaps to the lower and upper reference bound, when a reference is known.
+    pub gap_lb: Option<f64>,
+    pub gap_ub: Option<f64>,
+}
+
+impl RunRecord {
+    fn new(instance: &Instance, seed: u64, res: &TabuResult) -> Self {
+        Self {
+            instance_name: instance.name.clone(),
+            seed,
+            bins: res.best_bins,
+            unused: res.best_unused,
+            elapsed_s: res.elapsed.as_secs_f64(),
+            iters: res.iters,
+            stop_reason: res.stop_reason,
+            gap_lb: None,
+            gap_ub: None,
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct RunSummary {
@@ -13,6 +47,7 @@ pub struct RunSummary {
     pub std_obj: f64,
     pub mean_time_s: f64,
     pub best_time_s: f64,
+    pub runs: Vec<RunRecord>,
 }
 
 fn mean(values: &[f64]) -> f64 {
@@ -28,89 +63,90 @@ fn pstdev(values: &[f64]) -> f64 {
     var.sqrt()
 }
 
+fn summarize(instance: &Instance, records: Vec<RunRecord>) -> RunSummary {
+    let objs_f: Vec<f64> = records.iter().map(|r| r.bins as f64).collect();
+    let times_f: Vec<f64> = records.iter().map(|r| r.elapsed_s).collect();
+
+    RunSummary {
+        instance_name: instance.name.clone(),
+        mean_obj: mean(&objs_f),
+        best_obj: records.iter().map(|r| r.bins).min().unwrap(),
+        std_obj: pstdev(&objs_f),
+        mean_time_s: mean(&times_f),
+        best_time_s: times_f
+            .iter()
+            .copied()
+            .reduce(f64::min)
+            .unwrap_or(0.0),
+        runs: with_known_optimum(instance, records),
+    }
+}
+
 pub fn run_instance(
     instance: &Instance,
     runs: u32,
     seed0: u64,
-    params: TabuParams,
+    solver: &dyn Solver,
 ) -> (RunSummary, Vec<usize>, Vec<Duration>) {
     let mut objs: Vec<usize> = Vec::with_capacity(runs as usize);
     let mut times: Vec<Duration> = Vec::with_capacity(runs as usize);
 
+    let mut records: Vec<RunRecord> = Vec::with_capacity(runs as usize);
     for r in 0..runs {
-        let res = tabu_search(instance, seed0 + (r as u64), params);
+        let seed = seed0 + (r as u64);
+        let res = solver.solve(instance, seed);
         objs.push(res.best_bins);
         times.push(res.elapsed);
+        records.push(RunRecord::new(instance, seed, &res));
     }
 
-    let objs_f: Vec<f64> = objs.iter().map(|&v| v as f64).collect();
-    let times_f: Vec<f64> = times.iter().map(|t| t.as_secs_f64()).collect();
-
-    let summary = RunSummary {
-        instance_name: instance.name.clone(),
-        mean_obj: mean(&objs_f),
-        best_obj: *objs.iter().min().unwrap(),
-        std_obj: pstdev(&objs_f),
-        mean_time_s: mean(&times_f),
-        best_time_s: times_f
-            .iter()
-            .copied()
-            .reduce(f64::min)
-            .unwrap_or(0.0),
-    };
-
-    (summary, objs, times)
+    (summarize(instance, records), objs, times)
 }
 
 pub fn run_instance_verbose<W: Write>(
     instance: &Instance,
     runs: u32,
     seed0: u64,
-    params: TabuParams,
+    solver: &dyn Solver,
     out: &mut W,
 ) -> (RunSummary, Vec<usize>, Vec<Duration>) {
     let mut objs: Vec<usize> = Vec::with_capacity(runs as usize);
     let mut times: Vec<Duration> = Vec::with_capacity(runs as usize);
+    let mut records: Vec<RunRecord> = Vec::with_capacity(runs as usize);
 
-    writeln!(out, "instance={} capacity={} n={}", instance.name, instance.capacity, instance.sizes.len()).ok();
+    writeln!(
+        out,
+        "instance={} capacity={} n={} solver={}",
+        instance.name,
+        instance.capacity,
+        instance.sizes.len(),
+        solver.name()
+    )
+    .ok();
     for r in 0..runs {
         let seed = seed0 + (r as u64);
         writeln!(out, "  run {}/{} seed={}", r + 1, runs, seed).ok();
         out.flush().ok();
 
-        let res = tabu_search(instance, seed, params);
+        let res = solver.solve(instance, seed);
         objs.push(res.best_bins);
         times.push(res.elapsed);
+        records.push(RunRecord::new(instance, seed, &res));
 
         writeln!(
             out,
-            "    result: bins={} unused={} time={:.4}s iters={}",
+            "    result: bins={} unused={} time={:.4}s iters={} iters/s={:.1}",
             res.best_bins,
             res.best_unused,
             res.elapsed.as_secs_f64(),
-            res.iters
+            res.iters,
+            res.iters_per_sec
         )
         .ok();
         out.flush().ok();
     }
 
-    let objs_f: Vec<f64> = objs.iter().map(|&v| v as f64).collect();
-    let times_f: Vec<f64> = times.iter().map(|t| t.as_secs_f64()).collect();
-
-    let summary = RunSummary {
-        instance_name: instance.name.clone(),
-        mean_obj: mean(&objs_f),
-        best_obj: *objs.iter().min().unwrap(),
-        std_obj: pstdev(&objs_f),
-        mean_time_s: mean(&times_f),
-        best_time_s: times_f
-            .iter()
-            .copied()
-            .reduce(f64::min)
-            .unwrap_or(0.0),
-    };
-
-    (summary, objs, times)
+    (summarize(instance, records), objs, times)
 }
 
 pub fn format_table(rows: &[RunSummary]) -> String {
@@ -136,26 +172,38 @@ pub fn format_table(rows: &[RunSummary]) -> String {
 #[derive(Clone, Debug)]
 pub struct ExactGapSummary {
     pub instance_name: String,
-    pub exact_bins: Option<usize>,
+    pub reference: Option<ExactRef>,
     pub mean_obj: f64,
     pub best_obj: usize,
     pub std_obj: f64,
     pub mean_time_s: f64,
     pub best_time_s: f64,
-    pub gap_per_run: Vec<f64>,
+    // Gaps to the reference's lower and upper bound; equal when the reference is proven.
+    pub gap_lb_per_run: Vec<f64>,
+    pub gap_ub_per_run: Vec<f64>,
+    pub runs: Vec<RunRecord>,
+}
+
+fn format_gaps(gaps: &[f64]) -> String {
+    if gaps.is_empty() {
+        return "-".to_string();
+    }
+    gaps.iter().map(|g| format!("{g:.2}")).collect::<Vec<_>>().join(",")
 }
 
 pub fn format_exact_gap_table(rows: &[ExactGapSummary]) -> String {
     let header = format!(
-        "{:<18}{:>7}{:>10}{:>10}{:>10}{:>14}{:>14}  {}",
+        "{:<18}{:>7}{:>7}{:>10}{:>10}{:>10}{:>14}{:>14}  {:<24}  {}",
         "Instance",
-        "Exact",
+        "LB",
+        "UB",
         "Mean",
         "Best",
         "StdDev",
         "Mean Time(s)",
         "Best Time(s)",
-        "Gap% (runs)"
+        "Gap% LB (runs)",
+        "Gap% UB (runs)"
     );
     let mut out = String::new();
     out.push_str(&header);
@@ -164,85 +212,154 @@ pub fn format_exact_gap_table(rows: &[ExactGapSummary]) -> String {
     out.push('\n');
 
     for r in rows {
-        let exact = r.exact_bins.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
-        let gaps = if r.gap_per_run.is_empty() {
-            "-".to_string()
-        } else {
-            r.gap_per_run
-                .iter()
-                .map(|g| format!("{g:.2}"))
-                .collect::<Vec<_>>()
-                .join(",")
+        let (lb, ub) = match &r.reference {
+            Some(ex) => (ex.lower_label(), ex.upper_label()),
+            None => ("-".to_string(), "-".to_string()),
         };
-
         out.push_str(&format!(
-            "{:<18}{:>7}{:>10.2}{:>10}{:>10.2}{:>14.4}{:>14.4}  {}\n",
+            "{:<18}{:>7}{:>7}{:>10.2}{:>10}{:>10.2}{:>14.4}{:>14.4}  {:<24}  {}\n",
             r.instance_name,
-            exact,
+            lb,
+            ub,
             r.mean_obj,
             r.best_obj,
             r.std_obj,
             r.mean_time_s,
             r.best_time_s,
-            gaps
+            format_gaps(&r.gap_lb_per_run),
+            format_gaps(&r.gap_ub_per_run)
         ));
     }
+    if rows.iter().any(|r| r.reference.is_some_and(|ex| !ex.proven)) {
+        out.push_str("* optimum not proven: LB/UB are bounds, Gap% LB overestimates the true gap\n");
+    }
     out
 }
 
-pub fn run_instance_with_exact(
-    instance: &Instance,
-    runs: u32,
-    seed0: u64,
-    params: TabuParams,
-) -> ExactGapSummary {
-    let mut objs: Vec<usize> = Vec::with_capacity(runs as usize);
-    let mut times: Vec<Duration> = Vec::with_capacity(runs as usize);
-    for r in 0..runs {
-        let res = tabu_search(instance, seed0 + (r as u64), params);
-        objs.push(res.best_bins);
-        times.push(res.elapsed);
+// The run-* commands skip the reference search, so only a known optimum gives a gap there.
+fn with_known_optimum(instance: &Instance, mut records: Vec<RunRecord>) -> Vec<RunRecord> {
+    if let Some(opt) = instance.opt_bins.or(instance.known_opt) {
+        for rec in &mut records {
+            rec.gap_lb = Some(gap_percent(rec.bins, opt));
+            rec.gap_ub = rec.gap_lb;
+        }
     }
+    records
+}
 
-    let objs_f: Vec<f64> = objs.iter().map(|&v| v as f64).collect();
-    let times_f: Vec<f64> = times.iter().map(|t| t.as_secs_f64()).collect();
+fn with_reference(exact: Option<&ExactRef>, mut records: Vec<RunRecord>) -> Vec<RunRecord> {
+    if let Some(ex) = exact {
+        for rec in &mut records {
+            rec.gap_lb = Some(ex.gap_lb(rec.bins));
+            rec.gap_ub = ex.gap_ub(rec.bins);
+        }
+    }
+    records
+}
 
-    let exact = exact_reference(instance);
-    let gap_per_run = match exact.as_ref() {
-        Some(ex) => objs.iter().map(|&b| gap_percent(b, ex.bins)).collect(),
-        None => Vec::new(),
-    };
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+// One row per (instance, seed); gap columns are empty without a reference.
+pub fn write_runs_csv<'a, W: Write>(
+    records: impl IntoIterator<Item = &'a RunRecord>,
+    out: &mut W,
+) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "instance,seed,bins,unused,elapsed_s,iters,stop_reason,gap_lb_percent,gap_ub_percent"
+    )?;
+    let gap = |g: Option<f64>| g.map(|v| format!("{v:.4}")).unwrap_or_default();
+    for r in records {
+        writeln!(
+            out,
+            "{},{},{},{},{:.6},{},{},{},{}",
+            csv_field(&r.instance_name),
+            r.seed,
+            r.bins,
+            r.unused,
+            r.elapsed_s,
+            r.iters,
+            r.stop_reason.name(),
+            gap(r.gap_lb),
+            gap(r.gap_ub)
+        )?;
+    }
+    Ok(())
+}
+
+fn gaps_per_run(exact: Option<&ExactRef>, objs: &[usize]) -> (Vec<f64>, Vec<f64>) {
+    match exact {
+        Some(ex) => (
+            objs.iter().map(|&b| ex.gap_lb(b)).collect(),
+            objs.iter().filter_map(|&b| ex.gap_ub(b)).collect(),
+        ),
+        None => (Vec::new(), Vec::new()),
+    }
+}
+
+fn summarize_with_exact(instance: &Instance, exact: Option<ExactRef>, records: Vec<RunRecord>) -> ExactGapSummary {
+    let objs: Vec<usize> = records.iter().map(|r| r.bins).collect();
+    let objs_f: Vec<f64> = objs.iter().map(|&v| v as f64).collect();
+    let times_f: Vec<f64> = records.iter().map(|r| r.elapsed_s).collect();
+    let (gap_lb_per_run, gap_ub_per_run) = gaps_per_run(exact.as_ref(), &objs);
 
     ExactGapSummary {
         instance_name: instance.name.clone(),
-        exact_bins: exact.map(|e| e.bins),
+        reference: exact,
         mean_obj: mean(&objs_f),
         best_obj: *objs.iter().min().unwrap(),
         std_obj: pstdev(&objs_f),
         mean_time_s: mean(&times_f),
         best_time_s: times_f.iter().copied().reduce(f64::min).unwrap_or(0.0),
-        gap_per_run,
+        gap_lb_per_run,
+        gap_ub_per_run,
+        runs: with_reference(exact.as_ref(), records),
     }
 }
 
+pub fn run_instance_with_exact(
+    instance: &Instance,
+    runs: u32,
+    seed0: u64,
+    solver: &dyn Solver,
+) -> ExactGapSummary {
+    let mut records: Vec<RunRecord> = Vec::with_capacity(runs as usize);
+    for r in 0..runs {
+        let seed = seed0 + (r as u64);
+        let res = solver.solve(instance, seed);
+        records.push(RunRecord::new(instance, seed, &res));
+    }
+
+    summarize_with_exact(instance, exact_reference(instance), records)
+}
+
 pub fn run_instance_with_exact_verbose<W: Write>(
     instance: &Instance,
     runs: u32,
     seed0: u64,
-    params: TabuParams,
+    solver: &dyn Solver,
     out: &mut W,
 ) -> ExactGapSummary {
-    let exact = exact_reference(instance).map(|e| e.bins);
-    let mut objs: Vec<usize> = Vec::with_capacity(runs as usize);
-    let mut times: Vec<Duration> = Vec::with_capacity(runs as usize);
+    let exact = exact_reference(instance);
+    let mut records: Vec<RunRecord> = Vec::with_capacity(runs as usize);
 
     writeln!(
         out,
-        "instance={} capacity={} n={} exact_bins={}",
+        "instance={} capacity={} n={} lb={} ub={} proven={} source={} solver={}",
         instance.name,
         instance.capacity,
         instance.sizes.len(),
-        exact.map(|v| v.to_string()).unwrap_or_else(|| "N/A".to_string())
+        exact.map(|e| e.lower_bound.to_string()).unwrap_or_else(|| "N/A".to_string()),
+        exact.and_then(|e| e.upper_bound).map(|v| v.to_string()).unwrap_or_else(|| "N/A".to_string()),
+        exact.is_some_and(|e| e.proven),
+        exact.map(|e| e.source_label()).unwrap_or_else(|| "N/A".to_string()),
+        solver.name()
     )
     .ok();
     out.flush().ok();
@@ -252,38 +369,540 @@ pub fn run_instance_with_exact_verbose<W: Write>(
         writeln!(out, "  run {}/{} seed={}", r + 1, runs, seed).ok();
         out.flush().ok();
 
-        let res = tabu_search(instance, seed, params);
-        objs.push(res.best_bins);
-        times.push(res.elapsed);
+        let res = solver.solve(instance, seed);
+        records.push(RunRecord::new(instance, seed, &res));
 
-        let gap = exact.map(|ex| gap_percent(res.best_bins, ex));
+        let gap_lb = exact.map(|ex| ex.gap_lb(res.best_bins));
+        let gap_ub = exact.and_then(|ex| ex.gap_ub(res.best_bins));
         writeln!(
             out,
-            "    found_bins={} gap_percent={} time={:.4}s iters={}",
+            "    found_bins={} gap_lb_percent={} gap_ub_percent={} time={:.4}s iters={} iters/s={:.1}",
             res.best_bins,
-            gap.map(|g| format!("{g:.2}")).unwrap_or_else(|| "N/A".to_string()),
+            gap_lb.map(|g| format!("{g:.2}")).unwrap_or_else(|| "N/A".to_string()),
+            gap_ub.map(|g| format!("{g:.2}")).unwrap_or_else(|| "N/A".to_string()),
             res.elapsed.as_secs_f64(),
-            res.iters
+            res.iters,
+            res.iters_per_sec
         )
         .ok();
         out.flush().ok();
     }
 
-    let objs_f: Vec<f64> = objs.iter().map(|&v| v as f64).collect();
-    let times_f: Vec<f64> = times.iter().map(|t| t.as_secs_f64()).collect();
-    let gap_per_run = match exact {
-        Some(ex) => objs.iter().map(|&b| gap_percent(b, ex)).collect(),
-        None => Vec::new(),
+    summarize_with_exact(instance, exact, records)
+}
+
+// Evaluates `f(0..n)` on up to `jobs` scoped threads. Workers take the next index as they
+// finish, so long and short jobs even out; results come back in index order.
+fn parallel_map<T: Send>(n: usize, jobs: usize, f: impl Fn(usize) -> T + Sync) -> Vec<T> {
+    if jobs <= 1 || n <= 1 {
+        return (0..n).map(f).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let (f, next) = (&f, &next);
+    let done: Vec<Vec<(usize, T)>> = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.min(n))
+            .map(|_| {
+                scope.spawn(move || {
+                    let mut mine = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= n {
+                            break mine;
+                        }
+                        mine.push((i, f(i)));
+                    }
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    });
+
+    let mut out: Vec<Option<T>> = (0..n).map(|_| None).collect();
+    for (i, v) in done.into_iter().flatten() {
+        out[i] = Some(v);
+    }
+    out.into_iter().map(|v| v.unwrap()).collect()
+}
+
+fn progress_line(progress: Option<&Mutex<dyn Write + Send + '_>>, rec: &RunRecord) {
+    if let Some(out) = progress {
+        let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(
+            out,
+            "  done instance={} seed={} bins={} unused={} time={:.4}s iters={} stop={}",
+            rec.instance_name,
+            rec.seed,
+            rec.bins,
+            rec.unused,
+            rec.elapsed_s,
+            rec.iters,
+            rec.stop_reason.name()
+        )
+        .ok();
+        out.flush().ok();
+    }
+}
+
+enum Job {
+    Reference(Option<ExactRef>),
+    Run(RunRecord),
+}
+
+// All runs of all instances, grouped per instance in seed order, plus the exact references
+// when asked for. The reference searches are queued first since they tend to be the longest.
+fn run_jobs(
+    instances: &[Instance],
+    runs: u32,
+    seed0: u64,
+    solver: &dyn Solver,
+    jobs: usize,
+    progress: Option<&Mutex<dyn Write + Send + '_>>,
+    references: bool,
+) -> (Vec<Option<ExactRef>>, Vec<Vec<RunRecord>>) {
+    let runs = runs as usize;
+    let n_ref = if references { instances.len() } else { 0 };
+    let done = parallel_map(n_ref + instances.len() * runs, jobs, |j| {
+        if j < n_ref {
+            return Job::Reference(exact_reference(&instances[j]));
+        }
+        let (instance, seed) = (&instances[(j - n_ref) / runs], seed0 + ((j - n_ref) % runs) as u64);
+        let rec = RunRecord::new(instance, seed, &solver.solve(instance, seed));
+        progress_line(progress, &rec);
+        Job::Run(rec)
+    });
+
+    let mut exact = Vec::with_capacity(n_ref);
+    let mut flat = Vec::with_capacity(instances.len() * runs);
+    for job in done {
+        match job {
+            Job::Reference(ex) => exact.push(ex),
+            Job::Run(rec) => flat.push(rec),
+        }
+    }
+    let mut flat = flat.into_iter();
+    let records = instances.iter().map(|_| flat.by_ref().take(runs).collect()).collect();
+    (exact, records)
+}
+
+// Runs every (instance, seed) pair on `jobs` worker threads. Summaries are the same as from
+// `run_instance` in a loop, except for timings; with `jobs <= 1` that loop is what runs, and
+// `progress` gets the usual verbose output instead of one line per finished run.
+pub fn run_instances(
+    instances: &[Instance],
+    runs: u32,
+    seed0: u64,
+    solver: &dyn Solver,
+    jobs: usize,
+    progress: Option<&Mutex<dyn Write + Send + '_>>,
+) -> Vec<RunSummary> {
+    if jobs <= 1 {
+        return instances
+            .iter()
+            .map(|inst| match progress {
+                Some(out) => {
+                    let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+                    run_instance_verbose(inst, runs, seed0, solver, &mut &mut *out).0
+                }
+                None => run_instance(inst, runs, seed0, solver).0,
+            })
+            .collect();
+    }
+    run_jobs(instances, runs, seed0, solver, jobs, progress, false)
+        .1
+        .into_iter()
+        .zip(instances)
+        .map(|(records, inst)| summarize(inst, records))
+        .collect()
+}
+
+// `run_instances` with a reference per instance; the reference searches share the pool.
+pub fn run_instances_with_exact(
+    instances: &[Instance],
+    runs: u32,
+    seed0: u64,
+    solver: &dyn Solver,
+    jobs: usize,
+    progress: Option<&Mutex<dyn Write + Send + '_>>,
+) -> Vec<ExactGapSummary> {
+    if jobs <= 1 {
+        return instances
+            .iter()
+            .map(|inst| match progress {
+                Some(out) => {
+                    let mut out = out.lock().unwrap_or_else(|e| e.into_inner());
+                    run_instance_with_exact_verbose(inst, runs, seed0, solver, &mut &mut *out)
+                }
+                None => run_instance_with_exact(inst, runs, seed0, solver),
+            })
+            .collect();
+    }
+    let (exact, records) = run_jobs(instances, runs, seed0, solver, jobs, progress, true);
+    instances
+        .iter()
+        .zip(exact.into_iter().zip(records))
+        .map(|(inst, (exact, records))| summarize_with_exact(inst, exact, records))
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LatexColumn {
+    Instance,
+    Lb,
+    Ub,
+    Mean,
+    Best,
+    StdDev,
+    MeanTime,
+    BestTime,
+    GapLb,
+    GapUb,
+}
+
+impl LatexColumn {
+    pub const ALL: [LatexColumn; 10] = [
+        LatexColumn::Instance,
+        LatexColumn::Lb,
+        LatexColumn::Ub,
+        LatexColumn::Mean,
+        LatexColumn::Best,
+        LatexColumn::StdDev,
+        LatexColumn::MeanTime,
+        LatexColumn::BestTime,
+        LatexColumn::GapLb,
+        LatexColumn::GapUb,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LatexColumn::Instance => "instance",
+            LatexColumn::Lb => "lb",
+            LatexColumn::Ub => "ub",
+            LatexColumn::Mean => "mean",
+            LatexColumn::Best => "best",
+            LatexColumn::StdDev => "std",
+            LatexColumn::MeanTime => "mean-time",
+            LatexColumn::BestTime => "best-time",
+            LatexColumn::GapLb => "gap-lb",
+            LatexColumn::GapUb => "gap-ub",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    // Comma-separated column names, e.g. "instance,lb,best,gap-lb".
+    pub fn parse_list(list: &str) -> Option<Vec<Self>> {
+        list.split(',').map(Self::parse).collect()
+    }
+
+    fn header(self) -> &'static str {
+        match self {
+            LatexColumn::Instance => "Instance",
+            LatexColumn::Lb => "LB",
+            LatexColumn::Ub => "UB",
+            LatexColumn::Mean => "Mean Obj",
+            LatexColumn::Best => "Best Obj",
+            LatexColumn::StdDev => "Std. Dev.",
+            LatexColumn::MeanTime => "Mean Time [s]",
+            LatexColumn::BestTime => "Best Time [s]",
+            LatexColumn::GapLb => "Gap\\% LB",
+            LatexColumn::GapUb => "Gap\\% UB",
+        }
+    }
+
+    fn spec(self, longtable: bool) -> &'static str {
+        match self {
+            LatexColumn::Instance => ">{\\raggedright\\arraybackslash}p{2.7cm}",
+            LatexColumn::GapLb | LatexColumn::GapUb if longtable => ">{\\raggedright\\arraybackslash}p{3.2cm}",
+            LatexColumn::GapLb | LatexColumn::GapUb => ">{\\raggedright\\arraybackslash}X",
+            _ => "r",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LatexOptions {
+    // Raw LaTeX; no \caption when empty.
+    pub caption: String,
+    pub label: Option<String>,
+    pub columns: Vec<LatexColumn>,
+    // A page-breaking longtable, or a tabularx in a table float for short results.
+    pub longtable: bool,
+}
+
+impl Default for LatexOptions {
+    fn default() -> Self {
+        Self {
+            caption: String::new(),
+            label: None,
+            columns: LatexColumn::ALL.to_vec(),
+            longtable: true,
+        }
+    }
+}
+
+pub fn latex_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+// One table row, already formatted; `proven` is false when LB/UB are bounds only.
+struct LatexRow {
+    cells: Vec<String>,
+    proven: bool,
+}
+
+fn latex_gaps(gaps: &[f64]) -> String {
+    if gaps.is_empty() {
+        return "-".to_string();
+    }
+    let list = gaps.iter().map(|g| format!("{g:.2}")).collect::<Vec<_>>().join(",\\allowbreak ");
+    format!("\\texttt{{{list}}}")
+}
+
+fn latex_row(
+    columns: &[LatexColumn],
+    name: &str,
+    reference: Option<&ExactRef>,
+    stats: (f64, usize, f64, f64, f64),
+    gaps: (&[f64], &[f64]),
+) -> LatexRow {
+    let proven = reference.is_none_or(|ex| ex.proven);
+    let mark = if proven { "" } else { "$^\\dagger$" };
+    let (mean_obj, best_obj, std_obj, mean_time_s, best_time_s) = stats;
+    let cells = columns
+        .iter()
+        .map(|col| match col {
+            LatexColumn::Instance => format!("\\texttt{{{}}}", latex_escape(name)),
+            LatexColumn::Lb => reference.map_or("-".to_string(), |ex| format!("{}{mark}", ex.lower_bound)),
+            LatexColumn::Ub => reference
+                .and_then(|ex| ex.upper_bound)
+                .map_or("-".to_string(), |ub| format!("{ub}{mark}")),
+            LatexColumn::Mean => format!("{mean_obj:.2}"),
+            LatexColumn::Best => best_obj.to_string(),
+            LatexColumn::StdDev => format!("{std_obj:.2}"),
+            LatexColumn::MeanTime => format!("{mean_time_s:.4}"),
+            LatexColumn::BestTime => format!("{best_time_s:.4}"),
+            LatexColumn::GapLb => latex_gaps(gaps.0),
+            LatexColumn::GapUb => latex_gaps(gaps.1),
+        })
+        .collect();
+    LatexRow { cells, proven }
+}
+
+fn latex_table(rows: &[LatexRow], opts: &LatexOptions) -> String {
+    let cols = &opts.columns;
+    let spec: String = cols.iter().map(|c| c.spec(opts.longtable)).collect();
+    let header = format!(
+        "{}\\\\\n",
+        cols.iter().map(|c| c.header()).collect::<Vec<_>>().join(" & ")
+    );
+    let caption = if opts.caption.is_empty() {
+        String::new()
+    } else {
+        let label = opts.label.as_ref().map(|l| format!("\\label{{{l}}}")).unwrap_or_default();
+        format!("\\caption{{{}}}{label}", opts.caption)
+    };
+    let note = if rows.iter().any(|r| !r.proven) {
+        format!(
+            "\\multicolumn{{{}}}{{@{{}}l}}{{\\footnotesize $^\\dagger$ optimum not proven: LB/UB are bounds}}\\\\\n",
+            cols.len()
+        )
+    } else {
+        String::new()
     };
 
-    ExactGapSummary {
-        instance_name: instance.name.clone(),
-        exact_bins: exact,
-        mean_obj: mean(&objs_f),
-        best_obj: *objs.iter().min().unwrap(),
-        std_obj: pstdev(&objs_f),
-        mean_time_s: mean(&times_f),
-        best_time_s: times_f.iter().copied().reduce(f64::min).unwrap_or(0.0),
-        gap_per_run,
+    let mut out = String::new();
+    out.push_str("{%\n");
+    if opts.longtable {
+        out.push_str("\\setlength{\\tabcolsep}{2pt}\n\\renewcommand{\\arraystretch}{0.90}\n");
+        out.push_str(&format!("\\begin{{longtable}}{{@{{}}{spec}@{{}}}}\n"));
+        if !caption.is_empty() {
+            out.push_str(&format!("{caption}\\\\\n"));
+        }
+        out.push_str(&format!("\\toprule\n{header}\\midrule\n\\endfirsthead\n"));
+        out.push_str(&format!("\\toprule\n{header}\\midrule\n\\endhead\n"));
+        out.push_str(&format!(
+            "\\midrule\n\\multicolumn{{{}}}{{r}}{{\\textit{{Continued on next page}}}}\\\\\n\\endfoot\n",
+            cols.len()
+        ));
+        out.push_str(&format!("\\bottomrule\n{note}\\endlastfoot\n"));
+    } else {
+        out.push_str("\\setlength{\\tabcolsep}{3pt}\n\\renewcommand{\\arraystretch}{1.00}\n\\small\n");
+        out.push_str("\\begin{table}[H]\n\\centering\n");
+        if !caption.is_empty() {
+            out.push_str(&format!("{caption}\n"));
+        }
+        // tabularx needs an X column to stretch; otherwise the table keeps its natural width.
+        if spec.contains('X') {
+            out.push_str(&format!("\\begin{{tabularx}}{{\\textwidth}}{{@{{}}{spec}@{{}}}}\n"));
+        } else {
+            out.push_str(&format!("\\begin{{tabular}}{{@{{}}{spec}@{{}}}}\n"));
+        }
+        out.push_str(&format!("\\toprule\n{header}\\midrule\n"));
+    }
+
+    for r in rows {
+        out.push_str(&r.cells.join(" & "));
+        out.push_str(" \\\\\n");
+    }
+
+    if opts.longtable {
+        out.push_str("\\end{longtable}\n");
+    } else {
+        let env = if spec.contains('X') { "tabularx" } else { "tabular" };
+        out.push_str(&format!("\\bottomrule\n{note}\\end{{{env}}}\n\\end{{table}}\n"));
+    }
+    out.push_str("}%\n");
+    out
+}
+
+// booktabs table of the report commands; needs the booktabs, longtable, array and tabularx
+// packages (and float for [H]).
+pub fn format_exact_gap_latex(rows: &[ExactGapSummary], opts: &LatexOptions) -> String {
+    let rows: Vec<LatexRow> = rows
+        .iter()
+        .map(|r| {
+            latex_row(
+                &opts.columns,
+                &r.instance_name,
+                r.reference.as_ref(),
+                (r.mean_obj, r.best_obj, r.std_obj, r.mean_time_s, r.best_time_s),
+                (&r.gap_lb_per_run, &r.gap_ub_per_run),
+            )
+        })
+        .collect();
+    latex_table(&rows, opts)
+}
+
+// Same layout for the run commands: no reference columns, gaps only from a dataset optimum.
+pub fn format_latex(rows: &[RunSummary], opts: &LatexOptions) -> String {
+    let rows: Vec<LatexRow> = rows
+        .iter()
+        .map(|r| {
+            let gap_lb: Vec<f64> = r.runs.iter().filter_map(|rec| rec.gap_lb).collect();
+            let gap_ub: Vec<f64> = r.runs.iter().filter_map(|rec| rec.gap_ub).collect();
+            latex_row(
+                &opts.columns,
+                &r.instance_name,
+                None,
+                (r.mean_obj, r.best_obj, r.std_obj, r.mean_time_s, r.best_time_s),
+                (&gap_lb, &gap_ub),
+            )
+        })
+        .collect();
+    latex_table(&rows, opts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact_compare::BoundSource;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::tabu::TabuParams;
+
+    #[test]
+    fn every_run_becomes_one_csv_row() {
+        let mut inst = example_instance_tp2();
+        inst.name = "tp2, quoted".to_string();
+        let params = TabuParams {
+            max_iters: 200,
+            time_limit: None,
+            ..TabuParams::default()
+        };
+        let (summary, objs, _) = run_instance(&inst, 3, 5, &params);
+        assert_eq!(summary.runs.len(), 3);
+        assert_eq!(summary.runs.iter().map(|r| r.bins).collect::<Vec<_>>(), objs);
+        assert_eq!(summary.runs[2].seed, 7);
+
+        let mut buf = Vec::new();
+        write_runs_csv(&summary.runs, &mut buf).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("instance,seed,bins"));
+        // TP2 has a dataset optimum of 4, which the search reaches.
+        assert!(lines[1].starts_with("\"tp2, quoted\",5,4,"), "{}", lines[1]);
+        let tail = format!(",{},0.0000,0.0000", summary.runs[0].stop_reason.name());
+        assert!(lines[1].ends_with(&tail), "{}", lines[1]);
+    }
+
+    #[test]
+    fn parallel_runs_give_the_sequential_summaries() {
+        let mut insts: Vec<Instance> = (0..3).map(|k| synthetic_instance(&format!("par{k}"), 16, 100, 10, 60, k)).collect();
+        insts.push(example_instance_tp2());
+        let params = TabuParams {
+            max_iters: 300,
+            time_limit: None,
+            ..TabuParams::default()
+        };
+        let key = |r: &RunRecord| (r.instance_name.clone(), r.seed, r.bins, r.unused, r.iters, r.gap_lb, r.gap_ub);
+
+        let seq = run_instances(&insts, 3, 11, &params, 1, None);
+        let par = run_instances(&insts, 3, 11, &params, 4, None);
+        assert_eq!(seq.len(), par.len());
+        for (a, b) in seq.iter().zip(&par) {
+            assert_eq!((&a.instance_name, a.mean_obj, a.best_obj, a.std_obj), (&b.instance_name, b.mean_obj, b.best_obj, b.std_obj));
+            assert_eq!(a.runs.iter().map(key).collect::<Vec<_>>(), b.runs.iter().map(key).collect::<Vec<_>>());
+        }
+
+        let seq = run_instances_with_exact(&insts, 2, 0, &params, 1, None);
+        let par = run_instances_with_exact(&insts, 2, 0, &params, 3, None);
+        for (a, b) in seq.iter().zip(&par) {
+            assert_eq!(a.reference.map(|e| (e.lower_bound, e.upper_bound)), b.reference.map(|e| (e.lower_bound, e.upper_bound)));
+            assert_eq!((a.best_obj, &a.gap_lb_per_run, &a.gap_ub_per_run), (b.best_obj, &b.gap_lb_per_run, &b.gap_ub_per_run));
+            assert_eq!(a.runs.iter().map(key).collect::<Vec<_>>(), b.runs.iter().map(key).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn latex_table_escapes_names_and_marks_unproven_references() {
+        let exact = ExactRef {
+            lower_bound: 10,
+            lower_source: BoundSource::LpBound,
+            upper_bound: Some(11),
+            upper_source: Some(BoundSource::BinCompletion),
+            proven: false,
+        };
+        let row = ExactGapSummary {
+            instance_name: "u250_00 & #1".to_string(),
+            reference: Some(exact),
+            mean_obj: 11.0,
+            best_obj: 11,
+            std_obj: 0.0,
+            mean_time_s: 1.0,
+            best_time_s: 1.0,
+            gap_lb_per_run: vec![10.0, 10.0],
+            gap_ub_per_run: vec![0.0, 0.0],
+            runs: Vec::new(),
+        };
+        let opts = LatexOptions {
+            caption: "Results".to_string(),
+            columns: LatexColumn::parse_list("instance,lb,ub,gap-lb").unwrap(),
+            ..LatexOptions::default()
+        };
+        let tex = format_exact_gap_latex(&[row], &opts);
+        assert!(tex.contains("\\begin{longtable}{@{}>{\\raggedright\\arraybackslash}p{2.7cm}rr>{\\raggedright\\arraybackslash}p{3.2cm}@{}}"));
+        assert!(tex.contains("Instance & LB & UB & Gap\\% LB\\\\"));
+        assert!(tex.contains("\\texttt{u250\\_00 \\& \\#1} & 10$^\\dagger$ & 11$^\\dagger$ & \\texttt{10.00,\\allowbreak 10.00} \\\\"));
+        assert!(tex.contains("optimum not proven"));
+        assert!(LatexColumn::parse_list("instance,nope").is_none());
     }
 }
@@ -0,0 +1,314 @@
+use crate::instances::{synthetic_instance, Instance};
+use crate::rng::XorShift64;
+
+pub const TRIPLET_CAPACITY: u32 = 1000;
+
+// Falkenauer's triplet construction (the t60/t120/t249/t501 sets): each of the `bins` bins is
+// filled exactly by three items, drawn as 380..=490, then 250..=(C - first) / 2, then the rest.
+// The sizes add up to `bins` × C, so `bins` is the optimum; all of them lie in [C/4, C/2).
+pub fn triplet_instance(name: &str, bins: usize, seed: u64) -> Instance {
+    let mut rng = XorShift64::new(seed);
+    let mut sizes = Vec::with_capacity(3 * bins);
+    for _ in 0..bins {
+        let a = rng.gen_range_u32(380, 490);
+        let b = rng.gen_range_u32(250, (TRIPLET_CAPACITY - a) / 2);
+        sizes.extend([a, b, TRIPLET_CAPACITY - a - b]);
+    }
+    rng.shuffle(&mut sizes);
+    Instance {
+        name: name.to_string(),
+        capacity: TRIPLET_CAPACITY,
+        sizes,
+        opt_bins: Some(bins),
+        best_known: None,
+        known_opt: None,
+    }
+}
+
+// `count` triplet instances of `items` items, named like the originals (`t60_00`, ...).
+pub fn triplet_class(items: usize, count: usize, seed: u64) -> Result<Vec<Instance>, String> {
+    if items == 0 || !items.is_multiple_of(3) {
+        return Err(format!("triplet instances need a positive multiple of 3 items, got {items}"));
+    }
+    Ok((0..count)
+        .map(|k| triplet_instance(&format!("t{items}_{k:02}"), items / 3, seed.wrapping_add(k as u64)))
+        .collect())
+}
+
+// Letter suffix of the Scholl instance names (`_A`, `_B`, ...), a number past `Z`.
+fn instance_letter(k: usize) -> String {
+    if k < 26 {
+        ((b'A' + k as u8) as char).to_string()
+    } else {
+        k.to_string()
+    }
+}
+
+// Uniform value in the open interval (lo, hi) that is `residue` modulo `m`.
+fn draw_residue(rng: &mut XorShift64, lo: i64, hi: i64, residue: i64, m: i64) -> Option<i64> {
+    let first = lo + 1 + (residue - (lo + 1)).rem_euclid(m);
+    if first >= hi {
+        return None;
+    }
+    let count = ((hi - 1 - first) / m + 1) as usize;
+    Some(first + m * rng.gen_range_usize(count) as i64)
+}
+
+// Three sizes in (C/4, C/2) adding up to `total`, the first two with the given residues mod `m`.
+fn exact_triple(rng: &mut XorShift64, capacity: u32, total: u32, residues: [i64; 2], m: i64) -> [u32; 3] {
+    let (lo, hi, total) = (capacity as i64 / 4, (capacity as i64 + 1) / 2, total as i64);
+    loop {
+        let Some(a) = draw_residue(rng, lo, hi, residues[0], m) else {
+            continue;
+        };
+        let Some(b) = draw_residue(rng, lo.max(total - a - hi), hi.min(total - a - lo), residues[1], m) else {
+            continue;
+        };
+        return [a as u32, b as u32, (total - a - b) as u32];
+    }
+}
+
+fn check_ai_params(bins: usize, capacity: u32) -> Result<(), String> {
+    if capacity < 100 {
+        return Err(format!("capacity must be at least 100, got {capacity}"));
+    }
+    if bins < 2 {
+        return Err(format!("need at least 2 bins, got {bins}"));
+    }
+    Ok(())
+}
+
+// Augmented IRUP: `bins` bins filled exactly by three items each plus one more item, every size
+// in (C/4, C/2). The sizes add up to more than `bins` × C, so `bins + 1` is optimal and equal
+// to the rounded-up continuous bound.
+pub fn ai_instance(name: &str, bins: usize, capacity: u32, seed: u64) -> Result<Instance, String> {
+    check_ai_params(bins, capacity)?;
+    let mut rng = XorShift64::new(seed);
+    let mut sizes = Vec::with_capacity(3 * bins + 1);
+    for _ in 0..bins {
+        sizes.extend(exact_triple(&mut rng, capacity, capacity, [0, 0], 1));
+    }
+    sizes.push(capacity / 4 + 1 + rng.gen_range_u32(0, capacity / 4 - 2));
+    rng.shuffle(&mut sizes);
+    Ok(Instance {
+        name: name.to_string(),
+        capacity,
+        sizes,
+        opt_bins: Some(bins + 1),
+        best_known: None,
+        known_opt: None,
+    })
+}
+
+// Augmented non-IRUP: 3 × `bins` items in (C/4, C/2) adding up to exactly `bins` × C, so the
+// continuous bound is `bins`, but they admit no perfect packing. C is a multiple of 4 and every
+// size is 1 or 2 mod 4, so a full bin (three items) needs residues 1, 1, 2. Here `bins - 2`
+// triples are full and the other six items are all 2 mod 4 (two triples of C - 2 and C + 2),
+// which leaves too many 2s. Packing those six as three pairs gives the optimum, `bins + 1`.
+pub fn ani_instance(name: &str, bins: usize, capacity: u32, seed: u64) -> Result<Instance, String> {
+    check_ai_params(bins, capacity)?;
+    if !capacity.is_multiple_of(4) {
+        return Err(format!("ANI capacity must be a multiple of 4, got {capacity}"));
+    }
+    let mut rng = XorShift64::new(seed);
+    let mut sizes = Vec::with_capacity(3 * bins);
+    for _ in 0..bins - 2 {
+        sizes.extend(exact_triple(&mut rng, capacity, capacity, [1, 1], 4));
+    }
+    sizes.extend(exact_triple(&mut rng, capacity, capacity - 2, [2, 2], 4));
+    sizes.extend(exact_triple(&mut rng, capacity, capacity + 2, [2, 2], 4));
+    rng.shuffle(&mut sizes);
+    Ok(Instance {
+        name: name.to_string(),
+        capacity,
+        sizes,
+        opt_bins: Some(bins + 1),
+        best_known: None,
+        known_opt: None,
+    })
+}
+
+// Wäscher-style cutting-stock instance as CUTGEN draws them: `types` distinct sizes uniform in
+// [min_size, max_size], demands proportional to random weights and summing to `types` ×
+// `avg_demand`. Items of one type are listed together.
+pub fn cutgen_instance(
+    name: &str,
+    capacity: u32,
+    types: usize,
+    (min_size, max_size): (u32, u32),
+    avg_demand: usize,
+    seed: u64,
+) -> Instance {
+    let mut rng = XorShift64::new(seed);
+    let type_sizes: Vec<u32> = (0..types).map(|_| rng.gen_range_u32(min_size, max_size)).collect();
+    let weights: Vec<f64> = (0..types).map(|_| rng.gen_f64() + 1e-9).collect();
+    let total_weight: f64 = weights.iter().sum();
+    let n = types * avg_demand;
+    let mut demands: Vec<usize> = weights
+        .iter()
+        .map(|w| ((w / total_weight * n as f64) as usize).max(1))
+        .collect();
+    let assigned: usize = demands.iter().sum();
+    if let Some(last) = demands.last_mut() {
+        *last = (*last + n).saturating_sub(assigned).max(1);
+    }
+    let mut sizes = Vec::with_capacity(n);
+    for (&size, &d) in type_sizes.iter().zip(&demands) {
+        sizes.extend(std::iter::repeat_n(size, d));
+    }
+    Instance {
+        name: name.to_string(),
+        capacity,
+        sizes,
+        opt_bins: None,
+        best_known: None,
+        known_opt: None,
+    }
+}
+
+pub const CLASSES: &str =
+    "triplets, t60, t120, t249, t501, scholl1, scholl2, scholl3, schwerin1, schwerin2, waescher, ai, ani";
+
+// A whole benchmark class, `count` instances per parameter setting (the original counts by
+// default); instance `k` of the class uses seed `seed + k`.
+//   scholl1:   n in {50, 100, 200, 500}, C in {100, 120, 150}, sizes 1|20|30..=100 (N1C1W1_A, ...)
+//   scholl2:   n as above, C = 1000, mean size C/3|5|7|9 spread by 20|50|90% (N1W1B1R0, ...)
+//   scholl3:   n = 200, C = 100000, sizes 20000..=35000 (HARD0, ...)
+//   schwerin1/2: n = 100/120, C = 1000, sizes 150..=200
+//   waescher:  CUTGEN with C = 10000, 15..=60 item types of 500..=4000, average demand 4
+//   ai/ani:    n = 202|403|601|802|1003 (ai) or 201|402|600|801|1002 (ani) with C = 2500, 10000,
+//              20000, 40000, 80000
+pub fn generate_class(class: &str, count: Option<usize>, seed: u64) -> Result<Vec<Instance>, String> {
+    let mut out: Vec<Instance> = Vec::new();
+    let next_seed = |out: &Vec<Instance>| seed.wrapping_add(out.len() as u64);
+    const SCHOLL_N: [usize; 4] = [50, 100, 200, 500];
+    const AI_SIZES: [(usize, u32); 5] = [(67, 2500), (134, 10000), (200, 20000), (267, 40000), (334, 80000)];
+
+    match class {
+        "triplets" | "t60" => return triplet_class(60, count.unwrap_or(20), seed),
+        "t120" | "t249" | "t501" => return triplet_class(class[1..].parse().unwrap(), count.unwrap_or(20), seed),
+        "scholl1" => {
+            for (ni, &n) in SCHOLL_N.iter().enumerate() {
+                for (ci, capacity) in [100, 120, 150].into_iter().enumerate() {
+                    for (w, min_size) in [(1, 1), (2, 20), (4, 30)] {
+                        for k in 0..count.unwrap_or(20) {
+                            let name = format!("N{}C{}W{w}_{}", ni + 1, ci + 1, instance_letter(k));
+                            out.push(synthetic_instance(&name, n, capacity, min_size, 100, next_seed(&out)));
+                        }
+                    }
+                }
+            }
+        }
+        "scholl2" => {
+            for (ni, &n) in SCHOLL_N.iter().enumerate() {
+                for (wi, div) in [3, 5, 7, 9].into_iter().enumerate() {
+                    for (bi, percent) in [20, 50, 90].into_iter().enumerate() {
+                        let mean = 1000 / div;
+                        let spread = mean * percent / 100;
+                        for k in 0..count.unwrap_or(10) {
+                            let name = format!("N{}W{}B{}R{k}", ni + 1, wi + 1, bi + 1);
+                            out.push(synthetic_instance(&name, n, 1000, mean - spread, mean + spread, next_seed(&out)));
+                        }
+                    }
+                }
+            }
+        }
+        "scholl3" => {
+            for k in 0..count.unwrap_or(10) {
+                out.push(synthetic_instance(&format!("HARD{k}"), 200, 100_000, 20_000, 35_000, next_seed(&out)));
+            }
+        }
+        "schwerin1" | "schwerin2" => {
+            let n = if class == "schwerin1" { 100 } else { 120 };
+            for k in 0..count.unwrap_or(100) {
+                out.push(synthetic_instance(&format!("{class}_{k:03}"), n, 1000, 150, 200, next_seed(&out)));
+            }
+        }
+        "waescher" => {
+            for k in 0..count.unwrap_or(17) {
+                let s = next_seed(&out);
+                let types = XorShift64::new(s).gen_range_u32(15, 60) as usize;
+                out.push(cutgen_instance(&format!("waescher_{k:02}"), 10_000, types, (500, 4000), 4, s));
+            }
+        }
+        "ai" | "ani" => {
+            for (bins, capacity) in AI_SIZES {
+                let n = if class == "ai" { 3 * bins + 1 } else { 3 * bins };
+                for k in 0..count.unwrap_or(50) {
+                    let name = format!("{class}{n}_{k:02}");
+                    let inst = if class == "ai" {
+                        ai_instance(&name, bins, capacity, next_seed(&out))?
+                    } else {
+                        ani_instance(&name, bins, capacity, next_seed(&out))?
+                    };
+                    out.push(inst);
+                }
+            }
+        }
+        other => return Err(format!("Unknown instance class: {other} (expected one of {CLASSES})")),
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{load_bpp_instances_from_dir, parse_binpack_multi, write_binpack_multi, write_instance_dir};
+    use crate::packing::exact_min_bins;
+    use std::path::Path;
+
+    #[test]
+    fn triplets_fill_every_bin_and_survive_the_binpack_format() {
+        let class = triplet_class(60, 3, 7).unwrap();
+        assert_eq!(class[2].name, "t60_02");
+        for inst in &class {
+            assert_eq!(inst.sizes.len(), 60);
+            assert_eq!(inst.sizes.iter().sum::<u32>(), 20 * TRIPLET_CAPACITY);
+            assert!(inst.sizes.iter().all(|&s| (250..500).contains(&s)));
+        }
+        assert!(triplet_class(50, 1, 0).is_err());
+
+        let mut buf = Vec::new();
+        write_binpack_multi(&class, &mut buf).unwrap();
+        let back = parse_binpack_multi(Path::new("gen.txt"), &String::from_utf8(buf).unwrap()).unwrap();
+        assert_eq!(back.len(), 3);
+        assert_eq!(back[1].name, "gen_t60_01");
+        assert_eq!((back[1].capacity, &back[1].sizes, back[1].opt_bins), (class[1].capacity, &class[1].sizes, Some(20)));
+    }
+
+    #[test]
+    fn benchmark_classes_have_their_shape_and_stated_optimum() {
+        let scholl1 = generate_class("scholl1", Some(1), 0).unwrap();
+        assert_eq!(scholl1.len(), 36);
+        assert_eq!(scholl1[0].name, "N1C1W1_A");
+        let last = &scholl1[35];
+        assert_eq!((last.name.as_str(), last.sizes.len(), last.capacity), ("N4C3W4_A", 500, 150));
+        assert!(last.sizes.iter().all(|&s| (30..=100).contains(&s)));
+        assert_eq!(generate_class("scholl2", Some(2), 0).unwrap().len(), 96);
+        assert_eq!(generate_class("scholl3", None, 0).unwrap().len(), 10);
+        let waescher = generate_class("waescher", Some(2), 3).unwrap();
+        assert!(waescher.iter().all(|w| w.sizes.len() >= 15 * 4 && w.capacity == 10_000));
+        assert!(generate_class("nope", None, 0).is_err());
+
+        // Small enough for the exact solver to confirm the constructed optimum.
+        for seed in 0..3 {
+            let ani = ani_instance("ani", 5, 100, seed).unwrap();
+            assert_eq!(ani.sizes.iter().sum::<u32>(), 500);
+            assert!(ani.sizes.iter().all(|&s| s > 25 && s < 50 && matches!(s % 4, 1 | 2)));
+            assert_eq!(exact_min_bins(&ani).unwrap(), 6);
+            let ai = ai_instance("ai", 4, 120, seed).unwrap();
+            assert_eq!(exact_min_bins(&ai).unwrap(), ai.opt_bins.unwrap());
+        }
+        assert!(ani_instance("ani", 5, 102, 0).is_err());
+
+        let dir = std::env::temp_dir().join(format!("cse480tp3-generate-{}", std::process::id()));
+        let class = generate_class("ani", Some(1), 9).unwrap();
+        write_instance_dir(&class, &dir).unwrap();
+        let back = load_bpp_instances_from_dir(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(back.len(), 5);
+        let b = back.iter().find(|b| b.name == "ani402_00").unwrap();
+        let a = class.iter().find(|a| a.name == "ani402_00").unwrap();
+        assert_eq!((&b.sizes, b.capacity, b.opt_bins), (&a.sizes, 10_000, Some(135)));
+    }
+}
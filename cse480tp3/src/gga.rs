@@ -0,0 +1,348 @@
+use std::time::{Duration, Instant};
+
+use crate::bounds::best_lower_bound;
+use crate::instances::Instance;
+use crate::packing::{packing_objective, try_reduce_bins, BestFit, Decoder, Objective, Packing};
+use crate::rng::XorShift64;
+use crate::solver::Solver;
+use crate::tabu::{decreasing_order, iters_per_sec, StopReason, TabuResult};
+
+#[derive(Clone, Copy, Debug)]
+pub struct GgaParams {
+    // One iteration is one generation.
+    pub max_iters: u32,
+    pub time_limit: Option<Duration>,
+    pub population: usize,
+    pub offspring: usize,
+    pub tournament: usize,
+    pub mutation_rate: f64,
+    // Bins emptied by a mutation: the least-filled one plus random others.
+    pub mutation_bins: usize,
+    pub objective: Objective,
+}
+
+impl Default for GgaParams {
+    fn default() -> Self {
+        Self {
+            max_iters: 5_000,
+            time_limit: None,
+            population: 40,
+            offspring: 10,
+            tournament: 3,
+            mutation_rate: 0.3,
+            mutation_bins: 3,
+            objective: Objective::default(),
+        }
+    }
+}
+
+impl Solver for GgaParams {
+    fn name(&self) -> String {
+        "gga".to_string()
+    }
+
+    fn solve(&self, instance: &Instance, seed: u64) -> TabuResult {
+        grouping_genetic_algorithm(instance, seed, *self)
+    }
+}
+
+#[derive(Clone)]
+struct Individual {
+    packing: Packing,
+    fitness: (usize, f64),
+}
+
+impl Individual {
+    fn new(packing: Packing, objective: Objective) -> Self {
+        let fitness = objective.score(&packing);
+        Self { packing, fitness }
+    }
+}
+
+// Largest sum of one or two free items (sorted by decreasing size) that is at most `limit`,
+// returned with the positions of the chosen items in `free`.
+fn best_free_fill(instance: &Instance, free: &[usize], limit: u32) -> Option<(u32, Vec<usize>)> {
+    let size = |k: usize| instance.sizes[free[k]];
+    let mut best: Option<(u32, Vec<usize>)> = None;
+
+    if let Some(k) = (0..free.len()).find(|&k| size(k) <= limit) {
+        best = Some((size(k), vec![k]));
+    }
+
+    // Two pointers over the decreasing list: lo walks down from the largest, hi up from the smallest.
+    if free.len() >= 2 {
+        let (mut lo, mut hi) = (0, free.len() - 1);
+        while lo < hi {
+            let sum = size(lo) + size(hi);
+            if sum > limit {
+                lo += 1;
+            } else {
+                if best.as_ref().is_none_or(|(s, _)| sum > *s) {
+                    best = Some((sum, vec![lo, hi]));
+                }
+                if hi == 0 {
+                    break;
+                }
+                hi -= 1;
+            }
+        }
+    }
+    best
+}
+
+// First way of swapping one or two items of `bin` for one or two free items that leaves the bin
+// fuller: (bin positions, free positions, size out, size in).
+fn best_replacement(
+    instance: &Instance,
+    bin: &[usize],
+    load: u32,
+    free: &[usize],
+) -> Option<(Vec<usize>, Vec<usize>, u32, u32)> {
+    let m = bin.len();
+    let mut out_sets: Vec<Vec<usize>> = (0..m).map(|p| vec![p]).collect();
+    for p in 0..m {
+        for q in p + 1..m {
+            out_sets.push(vec![p, q]);
+        }
+    }
+
+    for out in out_sets {
+        let out_size: u32 = out.iter().map(|&p| instance.sizes[bin[p]]).sum();
+        let limit = instance.capacity - load + out_size;
+        if let Some((in_size, picks)) = best_free_fill(instance, free, limit) {
+            if in_size > out_size {
+                return Some((out, picks, out_size, in_size));
+            }
+        }
+    }
+    None
+}
+
+// Falkenauer's dominance-based local search: replace one or two items of a bin by one or two
+// free items that fill it better, then put whatever is still free back with best fit decreasing.
+fn repair(instance: &Instance, mut bins: Vec<Vec<usize>>, mut free: Vec<usize>) -> Packing {
+    let cap = instance.capacity;
+    let mut loads: Vec<u32> = bins
+        .iter()
+        .map(|b| b.iter().map(|&i| instance.sizes[i]).sum())
+        .collect();
+
+    let mut improved = true;
+    while improved && !free.is_empty() {
+        improved = false;
+        free.sort_by_key(|&i| std::cmp::Reverse(instance.sizes[i]));
+
+        for b in 0..bins.len() {
+            while let Some((out, picks, out_size, in_size)) = best_replacement(instance, &bins[b], loads[b], &free) {
+                let incoming: Vec<usize> = picks.iter().map(|&k| free[k]).collect();
+                for &k in picks.iter().rev() {
+                    free.remove(k);
+                }
+                for &p in out.iter().rev() {
+                    free.push(bins[b].swap_remove(p));
+                }
+                bins[b].extend(incoming);
+                loads[b] = loads[b] - out_size + in_size;
+                free.sort_by_key(|&i| std::cmp::Reverse(instance.sizes[i]));
+                improved = true;
+            }
+        }
+    }
+
+    free.sort_by_key(|&i| std::cmp::Reverse(instance.sizes[i]));
+    let mut assign = Vec::with_capacity(free.len());
+    BestFit.extend(instance, &free, &mut loads, &mut assign);
+    bins.resize(loads.len(), Vec::new());
+    for (&item, &b) in free.iter().zip(assign.iter()) {
+        bins[b].push(item);
+    }
+
+    let packing = Packing {
+        capacity: cap,
+        bins,
+        bin_loads: loads,
+    };
+    try_reduce_bins(instance, &packing)
+}
+
+fn tournament<'a>(pop: &'a [Individual], size: usize, rng: &mut XorShift64) -> &'a Individual {
+    let mut best = &pop[rng.gen_range_usize(pop.len())];
+    for _ in 1..size.max(1) {
+        let other = &pop[rng.gen_range_usize(pop.len())];
+        if other.fitness < best.fitness {
+            best = other;
+        }
+    }
+    best
+}
+
+// Bin-preserving crossover: a run of the donor's bins is copied into the receiver, the
+// receiver's bins that clash with it are dropped, and their other items get repaired in.
+fn crossover(instance: &Instance, receiver: &Packing, donor: &Packing, rng: &mut XorShift64) -> Packing {
+    let n_donor = donor.n_bins();
+    let a = rng.gen_range_usize(n_donor);
+    let b = rng.gen_range_usize(n_donor);
+    let (lo, hi) = if a <= b { (a, b + 1) } else { (b, a + 1) };
+
+    let mut injected = vec![false; instance.sizes.len()];
+    for bin in &donor.bins[lo..hi] {
+        for &i in bin {
+            injected[i] = true;
+        }
+    }
+
+    let mut bins: Vec<Vec<usize>> = Vec::with_capacity(receiver.n_bins() + hi - lo);
+    let mut free = Vec::new();
+    for bin in &receiver.bins {
+        if bin.iter().any(|&i| injected[i]) {
+            free.extend(bin.iter().copied().filter(|&i| !injected[i]));
+        } else {
+            bins.push(bin.clone());
+        }
+    }
+    let at = rng.gen_range_usize(bins.len() + 1);
+    bins.splice(at..at, donor.bins[lo..hi].iter().cloned());
+
+    repair(instance, bins, free)
+}
+
+fn mutate(instance: &Instance, packing: &Packing, n_bins: usize, rng: &mut XorShift64) -> Packing {
+    let mut bins = packing.bins.clone();
+    let mut free = Vec::new();
+
+    if let Some(emptiest) = (0..bins.len()).min_by_key(|&b| packing.bin_loads[b]) {
+        free.extend(bins.swap_remove(emptiest));
+    }
+    for _ in 1..n_bins {
+        if bins.is_empty() {
+            break;
+        }
+        let b = rng.gen_range_usize(bins.len());
+        free.extend(bins.swap_remove(b));
+    }
+
+    repair(instance, bins, free)
+}
+
+// Grouping genetic algorithm in the style of Falkenauer's HGGA: individuals are packings,
+// recombined bin-wise and repaired with the dominance local search. Returns the best packing's
+// bins concatenated as `best_order`.
+pub fn grouping_genetic_algorithm(instance: &Instance, seed: u64, params: GgaParams) -> TabuResult {
+    let n = instance.sizes.len();
+    let start = Instant::now();
+    let mut rng = XorShift64::new(seed);
+
+    let mut pop: Vec<Individual> = Vec::with_capacity(params.population.max(1));
+    let ffd = decreasing_order(instance, &mut rng);
+    pop.push(Individual::new(
+        try_reduce_bins(instance, &BestFit.pack(instance, &ffd)),
+        params.objective,
+    ));
+    while pop.len() < params.population.max(2) {
+        let mut order: Vec<usize> = (0..n).collect();
+        rng.shuffle(&mut order);
+        let packing = try_reduce_bins(instance, &BestFit.pack(instance, &order));
+        pop.push(Individual::new(packing, params.objective));
+    }
+
+    let best_of = |pop: &[Individual]| -> Individual {
+        pop.iter()
+            .min_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
+            .unwrap()
+            .clone()
+    };
+    let mut best = best_of(&pop);
+
+    let lb = best_lower_bound(instance);
+    let mut last_it = 0;
+    let mut reason = StopReason::MaxIters;
+
+    for it in 1..=params.max_iters {
+        if best.fitness.0 == lb || n < 2 {
+            reason = StopReason::LowerBound;
+            break;
+        }
+        last_it = it;
+        if let Some(limit) = params.time_limit {
+            if start.elapsed() >= limit {
+                reason = StopReason::TimeLimit;
+                break;
+            }
+        }
+
+        for _ in 0..params.offspring.max(1) {
+            let receiver = tournament(&pop, params.tournament, &mut rng);
+            let donor = tournament(&pop, params.tournament, &mut rng);
+            let mut child = crossover(instance, &receiver.packing, &donor.packing, &mut rng);
+            if rng.gen_f64() < params.mutation_rate {
+                child = mutate(instance, &child, params.mutation_bins, &mut rng);
+            }
+            let child = Individual::new(child, params.objective);
+
+            // Steady-state replacement of the worst individual, skipping exact fitness clones
+            // to keep some diversity in the population.
+            if pop.iter().any(|ind| ind.fitness == child.fitness) {
+                continue;
+            }
+            let worst = (0..pop.len())
+                .max_by(|&a, &b| pop[a].fitness.partial_cmp(&pop[b].fitness).unwrap())
+                .unwrap();
+            if child.fitness < pop[worst].fitness {
+                if child.fitness < best.fitness {
+                    best = child.clone();
+                }
+                pop[worst] = child;
+            }
+        }
+    }
+
+    let elapsed = start.elapsed();
+    let (best_bins, best_unused) = packing_objective(&best.packing);
+    TabuResult {
+        best_order: best.packing.bins.concat(),
+        best_packing: best.packing,
+        best_bins,
+        best_unused,
+        elapsed,
+        iters: last_it,
+        iters_per_sec: iters_per_sec(last_it, elapsed),
+        stop_reason: reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::packing::validate_packing;
+
+    #[test]
+    fn repair_keeps_every_item_exactly_once() {
+        let inst = synthetic_instance("gga-repair", 60, 150, 10, 100, 8);
+        let mut rng = XorShift64::new(2);
+        let mut order: Vec<usize> = (0..inst.sizes.len()).collect();
+        rng.shuffle(&mut order);
+        let a = BestFit.pack(&inst, &order);
+        rng.shuffle(&mut order);
+        let b = BestFit.pack(&inst, &order);
+
+        for _ in 0..50 {
+            let child = crossover(&inst, &a, &b, &mut rng);
+            validate_packing(&inst, &child).unwrap();
+            let mutant = mutate(&inst, &child, 3, &mut rng);
+            validate_packing(&inst, &mutant).unwrap();
+        }
+    }
+
+    #[test]
+    fn tp2_example_reaches_optimum() {
+        let inst = example_instance_tp2();
+        let params = GgaParams {
+            max_iters: 200,
+            ..GgaParams::default()
+        };
+        let res = grouping_genetic_algorithm(&inst, 0, params);
+        validate_packing(&inst, &res.best_packing).unwrap();
+        assert_eq!(res.best_bins, 4);
+    }
+}
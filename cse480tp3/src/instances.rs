@@ -1,4 +1,7 @@
 use crate::rng::XorShift64;
+use crate::cutting_stock::parse_cutting_stock;
+use crate::json::instances_from_json;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug)]
@@ -7,6 +10,10 @@ pub struct Instance {
     pub capacity: u32,
     pub sizes: Vec<u32>,
     pub opt_bins: Option<usize>,
+    // Best known bin count that is not proven optimal (e.g. from a time-limited MIP run).
+    pub best_known: Option<usize>,
+    // Bin count proven optimal outside the dataset, e.g. by a MIP solver run.
+    pub known_opt: Option<usize>,
 }
 
 fn decimal_places(s: &str) -> Option<usize> {
@@ -71,11 +78,19 @@ fn parse_decimal_scaled_u32(token: &str, scale: usize) -> Result<u32, String> {
 }
 
 fn parse_simple_single_instance(path: &Path, content: &str) -> Result<Instance, String> {
-    // Accept whitespace-separated integers; allow full-line comments starting with '#'.
+    // Accept whitespace-separated integers; allow full-line comments starting with '#'. A
+    // `# opt <bins>` comment gives the optimum, as `write_bpp_instance` writes it.
     let mut ints: Vec<u32> = Vec::new();
+    let mut opt_bins: Option<usize> = None;
     for line in content.lines() {
         let line = line.trim();
-        if line.is_empty() || line.starts_with('#') {
+        if let Some(comment) = line.strip_prefix('#') {
+            if let ["opt", v] = comment.split_whitespace().collect::<Vec<_>>()[..] {
+                opt_bins = v.parse().ok();
+            }
+            continue;
+        }
+        if line.is_empty() {
             continue;
         }
         for tok in line.split_whitespace() {
@@ -119,7 +134,7 @@ fn parse_simple_single_instance(path: &Path, content: &str) -> Result<Instance,
     if sizes.len() != n {
         return Err("Size count mismatch".to_string());
     }
-    if sizes.iter().any(|&s| s == 0) {
+    if sizes.contains(&0) {
         return Err("Item sizes must be > 0".to_string());
     }
     if let Some(&mx) = sizes.iter().max() {
@@ -138,11 +153,13 @@ fn parse_simple_single_instance(path: &Path, content: &str) -> Result<Instance,
         name,
         capacity: cap,
         sizes,
-        opt_bins: None,
+        opt_bins,
+        best_known: None,
+        known_opt: None,
     })
 }
 
-fn parse_binpack_multi(path: &Path, content: &str) -> Result<Vec<Instance>, String> {
+pub(crate) fn parse_binpack_multi(path: &Path, content: &str) -> Result<Vec<Instance>, String> {
     // Common "BinPack" multi-instance layout:
     //   K
     //   <name>
@@ -258,17 +275,76 @@ fn parse_binpack_multi(path: &Path, content: &str) -> Result<Vec<Instance>, Stri
             capacity: cap,
             sizes,
             opt_bins,
+            best_known: None,
+            known_opt: None,
         });
     }
 
     Ok(instances)
 }
 
+// The layout `parse_binpack_multi` reads, with integer sizes; the optimum goes into the header
+// when it is known.
+pub fn write_binpack_multi<W: Write>(instances: &[Instance], out: &mut W) -> std::io::Result<()> {
+    writeln!(out, "{}", instances.len())?;
+    for inst in instances {
+        writeln!(out, " {}", inst.name)?;
+        match inst.opt_bins {
+            Some(opt) => writeln!(out, " {} {} {}", inst.capacity, inst.sizes.len(), opt)?,
+            None => writeln!(out, " {} {}", inst.capacity, inst.sizes.len())?,
+        }
+        for size in &inst.sizes {
+            writeln!(out, "{size}")?;
+        }
+    }
+    Ok(())
+}
+
+// One instance in the simple `n capacity sizes...` layout (one value per line, as in BPPLIB),
+// preceded by an `# opt` comment when the optimum is known.
+pub fn write_bpp_instance<W: Write>(inst: &Instance, out: &mut W) -> std::io::Result<()> {
+    if let Some(opt) = inst.opt_bins {
+        writeln!(out, "# opt {opt}")?;
+    }
+    writeln!(out, "{}", inst.sizes.len())?;
+    writeln!(out, "{}", inst.capacity)?;
+    for size in &inst.sizes {
+        writeln!(out, "{size}")?;
+    }
+    Ok(())
+}
+
+// Writes `<dir>/<name>.txt` per instance, creating `dir` if needed; `load_bpp_instances_from_dir`
+// reads them back under the same names.
+pub fn write_instance_dir(instances: &[Instance], dir: impl AsRef<Path>) -> Result<(), String> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    for inst in instances {
+        let path = dir.join(format!("{}.txt", inst.name));
+        let mut w = std::io::BufWriter::new(
+            std::fs::File::create(&path).map_err(|e| format!("Failed to create {}: {e}", path.display()))?,
+        );
+        write_bpp_instance(inst, &mut w)
+            .and_then(|_| w.flush())
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
 pub fn load_bpp_instances_from_file(path: impl AsRef<Path>) -> Result<Vec<Instance>, String> {
     let path = path.as_ref();
     let content = std::fs::read_to_string(path)
         .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
 
+    let trimmed = content.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        return instances_from_json(&content).map_err(|e| format!("{}: {e}", path.display()));
+    }
+    // The cutting-stock layout has `size demand` pairs, which the simple format never does.
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("dataset");
+    if let Ok(cs) = parse_cutting_stock(stem, &content) {
+        return Ok(vec![cs.to_instance()]);
+    }
     if let Ok(inst) = parse_simple_single_instance(path, &content) {
         return Ok(vec![inst]);
     }
@@ -277,7 +353,7 @@ pub fn load_bpp_instances_from_file(path: impl AsRef<Path>) -> Result<Vec<Instan
     }
 
     Err(format!(
-        "Unrecognized dataset format in {}. Supported: simple integer instance, or BinPack multi-instance files.",
+        "Unrecognized dataset format in {}. Supported: simple integer instance, cutting-stock (size demand) instance, BinPack multi-instance files, or JSON.",
         path.display()
     ))
 }
@@ -324,6 +400,8 @@ pub fn example_instance_tp2() -> Instance {
         // Item sizes (1-indexed in the report): [22,17,45,12,38,27,19]
         sizes: vec![22, 17, 45, 12, 38, 27, 19],
         opt_bins: Some(4),
+        best_known: None,
+        known_opt: None,
     }
 }
 
@@ -345,6 +423,8 @@ pub fn synthetic_instance(
         capacity,
         sizes,
         opt_bins: None,
+        best_known: None,
+        known_opt: None,
     }
 }
 
@@ -0,0 +1,555 @@
+use std::fmt;
+
+use crate::exact_compare::ExactRef;
+use crate::experiments::{ExactGapSummary, RunRecord, RunSummary};
+use crate::instances::Instance;
+use crate::packing::Packing;
+use crate::tabu::TabuResult;
+
+// A small JSON value, enough for our own output and for reading instances back. Objects keep
+// their key order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Num(f64),
+    // Written exactly; f64 loses integers above 2^53, e.g. large seeds. The parser reads Num.
+    Int(u64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn obj(fields: Vec<(&str, Json)>) -> Json {
+        Json::Obj(fields.into_iter().map(|(k, v)| (k.to_string(), v)).collect())
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Obj(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Num(v) => Some(*v),
+            Json::Int(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    // Non-negative integers only.
+    pub fn as_usize(&self) -> Option<usize> {
+        self.as_f64()
+            .filter(|v| *v >= 0.0 && v.fract() == 0.0 && *v < 9.0e15)
+            .map(|v| v as usize)
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Arr(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    f.write_str("\"")
+}
+
+// Compact encoding; NaN and infinities become null.
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => f.write_str("null"),
+            Json::Bool(b) => write!(f, "{b}"),
+            Json::Num(v) if !v.is_finite() => f.write_str("null"),
+            Json::Num(v) if v.fract() == 0.0 && v.abs() < 1e15 => write!(f, "{}", *v as i64),
+            Json::Num(v) => write!(f, "{v}"),
+            Json::Int(v) => write!(f, "{v}"),
+            Json::Str(s) => write_str(f, s),
+            Json::Arr(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                f.write_str("]")
+            }
+            Json::Obj(fields) => {
+                f.write_str("{")?;
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(",")?;
+                    }
+                    write_str(f, k)?;
+                    write!(f, ":{v}")?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+// Deeper arrays and objects are rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 512;
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn err<T>(&self, what: &str) -> Result<T, String> {
+        Err(format!("JSON: {what} at byte {}", self.pos))
+    }
+
+    fn skip_ws(&mut self) {
+        while self.pos < self.bytes.len() && self.bytes[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_ws();
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), String> {
+        if self.peek() != Some(b) {
+            return self.err(&format!("expected `{}`", b as char));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn literal(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        if self.bytes[self.pos..].starts_with(word.as_bytes()) {
+            self.pos += word.len();
+            Ok(value)
+        } else {
+            self.err("invalid literal")
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        match self.peek() {
+            None => self.err("unexpected end of input"),
+            Some(b'n') => self.literal("null", Json::Null),
+            Some(b't') => self.literal("true", Json::Bool(true)),
+            Some(b'f') => self.literal("false", Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::Str),
+            Some(open @ (b'[' | b'{')) => {
+                if self.depth >= MAX_DEPTH {
+                    return self.err("nesting too deep");
+                }
+                self.depth += 1;
+                let v = if open == b'[' { self.array() } else { self.object() };
+                self.depth -= 1;
+                v
+            }
+            Some(_) => self.number(),
+        }
+    }
+
+    fn array(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Arr(items));
+        }
+        loop {
+            items.push(self.value()?);
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Arr(items));
+                }
+                _ => return self.err("expected `,` or `]`"),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, String> {
+        self.pos += 1;
+        let mut fields = Vec::new();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Obj(fields));
+        }
+        loop {
+            if self.peek() != Some(b'"') {
+                return self.err("expected a key");
+            }
+            let key = self.string()?;
+            self.expect(b':')?;
+            fields.push((key, self.value()?));
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Obj(fields));
+                }
+                _ => return self.err("expected `,` or `}`"),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        while self.pos < self.bytes.len() && matches!(self.bytes[self.pos], b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') {
+            self.pos += 1;
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).unwrap_or("");
+        match text.parse::<f64>() {
+            Ok(v) if !text.is_empty() => Ok(Json::Num(v)),
+            _ => {
+                self.pos = start;
+                self.err("invalid value")
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, String> {
+        let digits = self.bytes.get(self.pos..self.pos + 4).and_then(|h| std::str::from_utf8(h).ok());
+        let Some(v) = digits.and_then(|h| u32::from_str_radix(h, 16).ok()) else {
+            return self.err("invalid \\u escape");
+        };
+        self.pos += 4;
+        Ok(v)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let Some(&b) = self.bytes.get(self.pos) else {
+                return self.err("unterminated string");
+            };
+            self.pos += 1;
+            match b {
+                b'"' => return Ok(out),
+                b'\\' => {
+                    let Some(&e) = self.bytes.get(self.pos) else {
+                        return self.err("unterminated string");
+                    };
+                    self.pos += 1;
+                    match e {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'n' => out.push('\n'),
+                        b'r' => out.push('\r'),
+                        b't' => out.push('\t'),
+                        b'u' => {
+                            let mut c = self.hex4()?;
+                            if (0xD800..0xDC00).contains(&c) && self.bytes[self.pos..].starts_with(b"\\u") {
+                                self.pos += 2;
+                                let low = self.hex4()?;
+                                c = 0x10000 + ((c - 0xD800) << 10) + (low.wrapping_sub(0xDC00) & 0x3FF);
+                            }
+                            out.push(char::from_u32(c).unwrap_or('\u{FFFD}'));
+                        }
+                        _ => return self.err("invalid escape"),
+                    }
+                }
+                _ => {
+                    // Copy the whole UTF-8 sequence starting at this byte.
+                    let start = self.pos - 1;
+                    let mut end = self.pos;
+                    while end < self.bytes.len() && (self.bytes[end] & 0xC0) == 0x80 {
+                        end += 1;
+                    }
+                    match std::str::from_utf8(&self.bytes[start..end]) {
+                        Ok(s) => out.push_str(s),
+                        Err(_) => return self.err("invalid UTF-8"),
+                    }
+                    self.pos = end;
+                }
+            }
+        }
+    }
+}
+
+pub fn parse_json(text: &str) -> Result<Json, String> {
+    let mut p = Parser {
+        bytes: text.as_bytes(),
+        pos: 0,
+        depth: 0,
+    };
+    let v = p.value()?;
+    if p.peek().is_some() {
+        return p.err("trailing characters");
+    }
+    Ok(v)
+}
+
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+fn num<T: Into<f64>>(v: T) -> Json {
+    Json::Num(v.into())
+}
+
+fn count(v: usize) -> Json {
+    Json::Num(v as f64)
+}
+
+fn opt_count(v: Option<usize>) -> Json {
+    v.map_or(Json::Null, count)
+}
+
+fn counts(v: &[usize]) -> Json {
+    Json::Arr(v.iter().map(|&x| count(x)).collect())
+}
+
+fn floats(v: &[f64]) -> Json {
+    Json::Arr(v.iter().map(|&x| num(x)).collect())
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Json {
+        Json::Arr(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl ToJson for Instance {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("name", Json::Str(self.name.clone())),
+            ("capacity", num(self.capacity)),
+            ("sizes", Json::Arr(self.sizes.iter().map(|&s| num(s)).collect())),
+            ("opt_bins", opt_count(self.opt_bins)),
+            ("best_known", opt_count(self.best_known)),
+            ("known_opt", opt_count(self.known_opt)),
+        ])
+    }
+}
+
+// Item ids are 0-based indices into the instance's sizes.
+impl ToJson for Packing {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("capacity", num(self.capacity)),
+            ("bins", Json::Arr(self.bins.iter().map(|b| counts(b)).collect())),
+            ("bin_loads", Json::Arr(self.bin_loads.iter().map(|&l| num(l)).collect())),
+        ])
+    }
+}
+
+impl ToJson for TabuResult {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("best_bins", count(self.best_bins)),
+            ("best_unused", num(self.best_unused)),
+            ("elapsed_s", num(self.elapsed.as_secs_f64())),
+            ("iters", num(self.iters)),
+            ("iters_per_sec", num(self.iters_per_sec)),
+            ("stop_reason", Json::Str(self.stop_reason.name().to_string())),
+            ("best_order", counts(&self.best_order)),
+            ("best_packing", self.best_packing.to_json()),
+        ])
+    }
+}
+
+impl ToJson for RunRecord {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("instance_name", Json::Str(self.instance_name.clone())),
+            ("seed", Json::Int(self.seed)),
+            ("bins", count(self.bins)),
+            ("unused", num(self.unused)),
+            ("elapsed_s", num(self.elapsed_s)),
+            ("iters", num(self.iters)),
+            ("stop_reason", Json::Str(self.stop_reason.name().to_string())),
+            ("gap_lb_percent", self.gap_lb.map_or(Json::Null, num)),
+            ("gap_ub_percent", self.gap_ub.map_or(Json::Null, num)),
+        ])
+    }
+}
+
+impl ToJson for RunSummary {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("instance_name", Json::Str(self.instance_name.clone())),
+            ("mean_obj", num(self.mean_obj)),
+            ("best_obj", count(self.best_obj)),
+            ("std_obj", num(self.std_obj)),
+            ("mean_time_s", num(self.mean_time_s)),
+            ("best_time_s", num(self.best_time_s)),
+            ("runs", self.runs.to_json()),
+        ])
+    }
+}
+
+impl ToJson for ExactRef {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("lower_bound", count(self.lower_bound)),
+            ("lower_source", Json::Str(self.lower_source.name().to_string())),
+            ("upper_bound", opt_count(self.upper_bound)),
+            ("upper_source", self.upper_source.map_or(Json::Null, |s| Json::Str(s.name().to_string()))),
+            ("proven", Json::Bool(self.proven)),
+        ])
+    }
+}
+
+impl ToJson for ExactGapSummary {
+    fn to_json(&self) -> Json {
+        Json::obj(vec![
+            ("instance_name", Json::Str(self.instance_name.clone())),
+            ("reference", self.reference.as_ref().map_or(Json::Null, ToJson::to_json)),
+            ("mean_obj", num(self.mean_obj)),
+            ("best_obj", count(self.best_obj)),
+            ("std_obj", num(self.std_obj)),
+            ("mean_time_s", num(self.mean_time_s)),
+            ("best_time_s", num(self.best_time_s)),
+            ("gap_lb_per_run", floats(&self.gap_lb_per_run)),
+            ("gap_ub_per_run", floats(&self.gap_ub_per_run)),
+            ("runs", self.runs.to_json()),
+        ])
+    }
+}
+
+fn field<'a>(v: &'a Json, key: &str) -> Result<&'a Json, String> {
+    v.get(key).ok_or_else(|| format!("JSON: missing field `{key}`"))
+}
+
+fn u32_field(v: &Json, key: &str) -> Result<u32, String> {
+    field(v, key)?
+        .as_usize()
+        .and_then(|x| u32::try_from(x).ok())
+        .ok_or_else(|| format!("JSON: `{key}` must be a non-negative integer"))
+}
+
+fn opt_usize_field(v: &Json, key: &str) -> Result<Option<usize>, String> {
+    match v.get(key) {
+        None | Some(Json::Null) => Ok(None),
+        Some(x) => x
+            .as_usize()
+            .map(Some)
+            .ok_or_else(|| format!("JSON: `{key}` must be a non-negative integer or null")),
+    }
+}
+
+// The inverse of `Instance::to_json`; only `capacity` and `sizes` are required.
+pub fn instance_from_json(v: &Json) -> Result<Instance, String> {
+    let capacity = u32_field(v, "capacity")?;
+    let sizes = field(v, "sizes")?
+        .as_array()
+        .ok_or("JSON: `sizes` must be an array")?
+        .iter()
+        .map(|s| s.as_usize().and_then(|x| u32::try_from(x).ok()))
+        .collect::<Option<Vec<u32>>>()
+        .ok_or("JSON: item sizes must be non-negative integers")?;
+    if capacity == 0 || sizes.iter().any(|&s| s == 0 || s > capacity) {
+        return Err("JSON: sizes must be in 1..=capacity".to_string());
+    }
+    Ok(Instance {
+        name: v.get("name").and_then(Json::as_str).unwrap_or("json-instance").to_string(),
+        capacity,
+        sizes,
+        opt_bins: opt_usize_field(v, "opt_bins")?,
+        best_known: opt_usize_field(v, "best_known")?,
+        known_opt: opt_usize_field(v, "known_opt")?,
+    })
+}
+
+// The inverse of `Packing::to_json`.
+pub fn packing_from_json(v: &Json) -> Result<Packing, String> {
+    let list = |key: &str| -> Result<&[Json], String> {
+        field(v, key)?
+            .as_array()
+            .ok_or_else(|| format!("JSON: `{key}` must be an array"))
+    };
+    let bins = list("bins")?
+        .iter()
+        .map(|b| b.as_array().and_then(|ids| ids.iter().map(Json::as_usize).collect::<Option<Vec<usize>>>()))
+        .collect::<Option<Vec<_>>>()
+        .ok_or("JSON: `bins` must be arrays of item ids")?;
+    let bin_loads = list("bin_loads")?
+        .iter()
+        .map(|l| l.as_usize().and_then(|x| u32::try_from(x).ok()))
+        .collect::<Option<Vec<u32>>>()
+        .ok_or("JSON: `bin_loads` must be non-negative integers")?;
+    Ok(Packing {
+        capacity: u32_field(v, "capacity")?,
+        bins,
+        bin_loads,
+    })
+}
+
+// One instance object or an array of them.
+pub fn instances_from_json(text: &str) -> Result<Vec<Instance>, String> {
+    match parse_json(text)? {
+        Json::Arr(items) => items.iter().map(instance_from_json).collect(),
+        v => Ok(vec![instance_from_json(&v)?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::example_instance_tp2;
+    use crate::packing::best_fit_pack;
+
+    #[test]
+    fn instances_and_packings_round_trip() {
+        let mut inst = example_instance_tp2();
+        inst.name = "tp2 \"quoted\"\tname ü".to_string();
+        inst.best_known = Some(5);
+        let text = inst.to_json().to_string();
+        let back = instances_from_json(&text).unwrap();
+        assert_eq!(back.len(), 1);
+        assert_eq!(back[0].name, inst.name);
+        assert_eq!(back[0].sizes, inst.sizes);
+        assert_eq!((back[0].opt_bins, back[0].best_known), (Some(4), Some(5)));
+
+        let order: Vec<usize> = (0..inst.sizes.len()).collect();
+        let packing = best_fit_pack(&inst, &order);
+        let again = packing_from_json(&parse_json(&packing.to_json().to_string()).unwrap()).unwrap();
+        assert_eq!(again.bins, packing.bins);
+        assert_eq!(again.bin_loads, packing.bin_loads);
+
+        let v = parse_json(r#" {"a": [1, 2.5e1, -3], "b": "é😀", "c": null, "d": "\u00e9\ud83d\ude00"} "#).unwrap();
+        assert_eq!(v.get("a").unwrap().as_array().unwrap()[1], Json::Num(25.0));
+        assert_eq!(v.get("b").unwrap().as_str(), Some("é😀"));
+        assert_eq!(v.get("d").unwrap().as_str(), Some("é😀"));
+        assert_eq!(Json::Num(f64::NAN).to_string(), "null");
+        assert_eq!(Json::Int(u64::MAX).to_string(), "18446744073709551615");
+        assert!(parse_json("[1,]").is_err());
+        assert!(parse_json("{} x").is_err());
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(parse_json(&deep).unwrap_err().contains("nesting too deep"));
+        assert!(parse_json(&format!("{}{}", "[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH))).is_ok());
+    }
+}
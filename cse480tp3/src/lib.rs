@@ -1,7 +1,20 @@
+pub mod annealing;
+pub mod best_known;
+pub mod bounds;
+pub mod colgen;
+pub mod cutting_stock;
 pub mod experiments;
+pub mod exact;
 pub mod exact_compare;
+pub mod generators;
+pub mod gga;
 pub mod instances;
+pub mod json;
+pub mod mip;
+pub mod multistart;
 pub mod packing;
+pub mod reduction;
+pub mod solver;
 pub mod tabu;
 
 mod rng;
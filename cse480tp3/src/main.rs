@@ -1,19 +1,34 @@
+use std::io::Write;
+use std::sync::Mutex;
 use std::time::Duration;
 
+use cse480tp3::annealing::{AnnealingParams, Cooling};
+use cse480tp3::best_known::{apply_known_solutions, load_known_solutions};
+use cse480tp3::generators::{generate_class, triplet_class};
+use cse480tp3::gga::GgaParams;
+use cse480tp3::cutting_stock::{cutting_patterns, write_patterns};
 use cse480tp3::experiments::{
-    format_exact_gap_table, format_table, run_instance, run_instance_verbose, run_instance_with_exact,
-    run_instance_with_exact_verbose,
+    format_exact_gap_latex, format_exact_gap_table, format_latex, format_table, latex_escape, run_instances,
+    run_instances_with_exact, write_runs_csv, LatexColumn, LatexOptions, RunRecord,
 };
 use cse480tp3::exact_compare::compare_against_exact;
 use cse480tp3::instances::{
     default_batch_instances, example_instance_tp2, load_bpp_instances_from_dir, load_bpp_instances_from_file,
+    write_binpack_multi, write_instance_dir, Instance,
 };
-use cse480tp3::packing::{exact_min_bins, validate_packing};
-use cse480tp3::tabu::{tabu_search, tabu_search_trace, TabuParams, TraceConfig};
+use cse480tp3::json::ToJson;
+use cse480tp3::mip::{write_mip, MipFormat, MipModel};
+use cse480tp3::multistart::MultiStartParams;
+use cse480tp3::packing::{
+    exact_min_bins, packing_violations, read_solution, validate_packing, write_solution, DecoderKind, Objective,
+};
+use cse480tp3::reduction::Reduced;
+use cse480tp3::solver::Solver;
+use cse480tp3::tabu::{tabu_search, tabu_search_trace, SearchSpace, TabuParams, TraceConfig};
 
 fn usage() -> ! {
     eprintln!(
-        "Usage:\n  cargo run --release -- run-example\n  cargo run --release -- trace-tp2 [--iters N] [--samples K] [--tenure T] [--seed S] [--show-packings] [--no-candidates]\n  cargo run --release -- compare-exact-file <FILE> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T]\n  cargo run --release -- report-file <FILE> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T] [--progress]\n  cargo run --release -- report-batch [--runs N] [--seed0 S] [--time-limit-s T] [--progress]\n  cargo run --release -- run-batch [--runs N] [--seed0 S] [--time-limit-s T] [--progress]\n  cargo run --release -- run-file <FILE> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T] [--progress]\n  cargo run --release -- run-dir <DIR> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T] [--progress]\n"
+        "Usage:\n  cargo run --release -- run-example [--format text|json]\n  cargo run --release -- trace-tp2 [--iters N] [--samples K] [--tenure T] [--seed S] [--show-packings] [--no-candidates] [--decoder D] [--objective O]\n  cargo run --release -- trace-file <FILE> [--index I] [trace-tp2 options]\n  cargo run --release -- compare-exact-file <FILE> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T] [--decoder D] [--objective O] [--algo A] [--cooling C] [--reduce] [--known PATH]\n  cargo run --release -- report-file <FILE> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T] [--decoder D] [--objective O] [--algo A] [--cooling C] [--reduce] [--format text|json] [--csv PATH] [--latex PATH [--caption TEXT] [--label KEY] [--columns LIST] [--latex-style longtable|table]] [--known PATH] [--progress] [--jobs N]\n  cargo run --release -- report-batch [--runs N] [--seed0 S] [--time-limit-s T] [--decoder D] [--objective O] [--algo A] [--cooling C] [--reduce] [--format text|json] [--csv PATH] [--latex PATH [--caption TEXT] [--label KEY] [--columns LIST] [--latex-style longtable|table]] [--known PATH] [--progress] [--jobs N]\n  cargo run --release -- run-batch [--runs N] [--seed0 S] [--time-limit-s T] [--decoder D] [--objective O] [--algo A] [--cooling C] [--reduce] [--format text|json] [--csv PATH] [--latex PATH [--caption TEXT] [--label KEY] [--columns LIST] [--latex-style longtable|table]] [--progress] [--jobs N]\n  cargo run --release -- run-file <FILE> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T] [--decoder D] [--objective O] [--algo A] [--cooling C] [--reduce] [--format text|json] [--csv PATH] [--latex PATH [--caption TEXT] [--label KEY] [--columns LIST] [--latex-style longtable|table]] [--progress] [--jobs N]\n  cargo run --release -- run-dir <DIR> [--runs N] [--seed0 S] [--skip S] [--take K] [--time-limit-s T] [--decoder D] [--objective O] [--algo A] [--cooling C] [--reduce] [--format text|json] [--csv PATH] [--latex PATH [--caption TEXT] [--label KEY] [--columns LIST] [--latex-style longtable|table]] [--progress] [--jobs N]\n  cargo run --release -- export-mip <FILE> [--model assignment|arcflow] [--format lp|mps] [--index I] [--out PATH]\n  cargo run --release -- generate <CLASS> [--items N] [--count K] [--seed S] [--out PATH | --dir DIR]\n  cargo run --release -- solve <FILE> [--index I] [--seed S] [--time-limit-s T] [--decoder D] [--objective O] [--algo A] [--cooling C] [--reduce] [--out SOL] [--patterns]\n  cargo run --release -- validate <FILE> <SOL> [--index I]\n\nDecoders (--decoder): first-fit|ff, next-fit|nf, worst-fit|wf, almost-worst-fit|awf, best-fit|bf (default)\nObjectives (--objective): falkenauer[:K] (default, K=2), min-bin-items, squared-loads, bins-unused\nAlgorithms (--algo): tabu (default, permutations decoded by --decoder), bin-tabu (moves items between bins), multi-tabu[:T] (T cooperating tabu threads sharing elites, default one per core), annealing|sa, gga; cooling (--cooling, annealing only): geometric (default), linear, adaptive\n--reduce runs the algorithm on the instance left after the Martello-Toth reduction\n--format json prints the summaries as a JSON array instead of the table\n--csv writes one row per (instance, seed) run: bins, unused, elapsed, iters, stop reason and gaps\n--latex writes the table as booktabs LaTeX; --label adds a \\label{{KEY}}; --columns picks from instance,lb,ub,mean,best,std,mean-time,best-time,gap-lb,gap-ub\n--jobs N spreads the (instance, seed) runs over N worker threads (0: one per core); results keep the sequential order; not with multi-tabu\ngenerate writes a class as one BinPack multi-instance file, or with --dir one file per instance; classes: triplets (--items N), t60, t120, t249, t501, scholl1, scholl2, scholl3, schwerin1, schwerin2, waescher, ai, ani; --count is per parameter setting\n--patterns makes solve print the bins grouped into cutting patterns (`pattern <bins> <load>: <size>*<pieces> ...`) instead of a solution file\n--known reads best known / optimal bin counts from a .sol file, a directory of .sol files, or a `name bins proven` list\n"
     );
     std::process::exit(2);
 }
@@ -39,6 +54,14 @@ fn parse_usize(flag: &str, v: Option<&String>) -> usize {
     })
 }
 
+// 0 means one worker per available core.
+fn parse_jobs(flag: &str, v: Option<&String>) -> usize {
+    match parse_usize(flag, v) {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
 fn parse_f64(flag: &str, v: Option<&String>) -> f64 {
     v.unwrap_or_else(|| usage()).parse::<f64>().unwrap_or_else(|_| {
         eprintln!("Invalid value for {flag}");
@@ -46,19 +69,343 @@ fn parse_f64(flag: &str, v: Option<&String>) -> f64 {
     })
 }
 
-fn run_example() -> i32 {
+fn parse_decoder(flag: &str, v: Option<&String>) -> DecoderKind {
+    DecoderKind::parse(v.unwrap_or_else(|| usage())).unwrap_or_else(|| {
+        eprintln!("Invalid value for {flag} (expected first-fit, next-fit, worst-fit, almost-worst-fit or best-fit)");
+        usage()
+    })
+}
+
+fn parse_objective(flag: &str, v: Option<&String>) -> Objective {
+    Objective::parse(v.unwrap_or_else(|| usage())).unwrap_or_else(|| {
+        eprintln!("Invalid value for {flag} (expected falkenauer[:K], min-bin-items, squared-loads or bins-unused)");
+        usage()
+    })
+}
+
+fn parse_cooling(flag: &str, v: Option<&String>) -> Cooling {
+    Cooling::parse(v.unwrap_or_else(|| usage())).unwrap_or_else(|| {
+        eprintln!("Invalid value for {flag} (expected geometric, linear or adaptive)");
+        usage()
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+fn parse_format(flag: &str, v: Option<&String>) -> OutputFormat {
+    match v.unwrap_or_else(|| usage()).to_ascii_lowercase().as_str() {
+        "text" | "table" => OutputFormat::Text,
+        "json" => OutputFormat::Json,
+        _ => {
+            eprintln!("Invalid value for {flag} (expected text or json)");
+            usage()
+        }
+    }
+}
+
+fn parse_columns(flag: &str, v: Option<&String>) -> Vec<LatexColumn> {
+    LatexColumn::parse_list(v.unwrap_or_else(|| usage())).unwrap_or_else(|| {
+        let names = LatexColumn::ALL.map(|c| c.name()).join(", ");
+        eprintln!("Invalid value for {flag} (expected a comma-separated list of: {names})");
+        usage()
+    })
+}
+
+fn parse_latex_style(flag: &str, v: Option<&String>) -> bool {
+    match v.unwrap_or_else(|| usage()).to_ascii_lowercase().as_str() {
+        "longtable" => true,
+        "table" => false,
+        _ => {
+            eprintln!("Invalid value for {flag} (expected longtable or table)");
+            usage()
+        }
+    }
+}
+
+fn default_caption(source: &str, n_instances: usize, runs: u32) -> String {
+    let name = std::path::Path::new(source)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(source);
+    format!(
+        "Results on \\texttt{{{}}}, {n_instances} instances, {runs} runs each",
+        latex_escape(name)
+    )
+}
+
+fn build_solver(name: &str, params: TabuParams, cooling: Cooling, reduce: bool) -> Box<dyn Solver> {
+    let solver: Box<dyn Solver> = match name {
+        "tabu" => Box::new(params),
+        "bin-tabu" => Box::new(TabuParams {
+            space: SearchSpace::Bins,
+            ..params
+        }),
+        "annealing" | "sa" => Box::new(AnnealingParams {
+            time_limit: params.time_limit,
+            cooling,
+            decoder: params.decoder,
+            objective: params.objective,
+            ..AnnealingParams::default()
+        }),
+        "gga" => Box::new(GgaParams {
+            time_limit: params.time_limit,
+            objective: params.objective,
+            ..GgaParams::default()
+        }),
+        multi if multi.starts_with("multi-tabu") => {
+            let threads = match &multi["multi-tabu".len()..] {
+                "" => 0,
+                rest => rest.strip_prefix(':').and_then(|t| t.parse().ok()).unwrap_or_else(|| {
+                    eprintln!("Invalid thread count in --algo {multi}");
+                    usage()
+                }),
+            };
+            Box::new(MultiStartParams {
+                tabu: params,
+                threads,
+                ..MultiStartParams::default()
+            })
+        }
+        other => {
+            eprintln!("Unknown algorithm: {other} (expected tabu, bin-tabu, multi-tabu[:T], annealing or gga)");
+            usage()
+        }
+    };
+    if reduce {
+        Box::new(Reduced(solver))
+    } else {
+        solver
+    }
+}
+
+fn write_csv<'a>(path: &str, records: impl IntoIterator<Item = &'a RunRecord>) -> std::io::Result<()> {
+    let mut w = std::io::BufWriter::new(std::fs::File::create(path)?);
+    write_runs_csv(records, &mut w)?;
+    w.flush()
+}
+
+fn apply_known(path: Option<&str>, instances: &mut [Instance]) -> Result<(), String> {
+    let Some(path) = path else {
+        return Ok(());
+    };
+    let known = load_known_solutions(path)?;
+    let matched = apply_known_solutions(instances, &known);
+    eprintln!("known solutions: {} read, {} instance(s) matched", known.len(), matched);
+    Ok(())
+}
+
+// The flag groups a command can accept; `CommonOpts::parse` rejects anything outside them.
+const SOLVER_FLAGS: &[&str] = &["--time-limit-s", "--decoder", "--objective", "--algo", "--cooling", "--reduce"];
+const RUN_FLAGS: &[&str] = &["--runs", "--seed0"];
+const PARALLEL_FLAGS: &[&str] = &["--progress", "--jobs"];
+const SLICE_FLAGS: &[&str] = &["--skip", "--take"];
+const OUTPUT_FLAGS: &[&str] = &["--format", "--csv", "--latex", "--caption", "--label", "--columns", "--latex-style"];
+const KNOWN_FLAGS: &[&str] = &["--known"];
+
+// Options shared by the run/report commands, compare-exact-file and solve.
+struct CommonOpts {
+    runs: u32,
+    seed0: u64,
+    skip: usize,
+    take: Option<usize>,
+    time_limit_s: f64,
+    decoder: DecoderKind,
+    objective: Objective,
+    algo_name: String,
+    cooling: Cooling,
+    reduce: bool,
+    format: OutputFormat,
+    csv_path: Option<String>,
+    latex_path: Option<String>,
+    latex: LatexOptions,
+    known: Option<String>,
+    progress: bool,
+    jobs: usize,
+}
+
+impl Default for CommonOpts {
+    fn default() -> Self {
+        Self {
+            runs: 5,
+            seed0: 0,
+            skip: 0,
+            take: None,
+            time_limit_s: 2.0,
+            decoder: DecoderKind::BestFit,
+            objective: Objective::default(),
+            algo_name: "tabu".to_string(),
+            cooling: Cooling::Geometric { alpha: 0.95 },
+            reduce: false,
+            format: OutputFormat::Text,
+            csv_path: None,
+            latex_path: None,
+            latex: LatexOptions::default(),
+            known: None,
+            progress: false,
+            jobs: 1,
+        }
+    }
+}
+
+impl CommonOpts {
+    fn parse(args: &[String], groups: &[&[&str]]) -> Self {
+        let mut opts = Self::default();
+        let mut i = 0;
+        while i < args.len() {
+            i += opts.parse_flag(args, i, groups).unwrap_or_else(|| {
+                eprintln!("Unknown arg: {}", args[i]);
+                usage()
+            });
+        }
+        opts
+    }
+
+    // How many arguments the flag at `args[i]` takes, or None when it is not in `groups`.
+    fn parse_flag(&mut self, args: &[String], i: usize, groups: &[&[&str]]) -> Option<usize> {
+        let flag = args[i].as_str();
+        if flag == "--help" || flag == "-h" {
+            usage();
+        }
+        if !groups.iter().any(|g| g.contains(&flag)) {
+            return None;
+        }
+        let value = args.get(i + 1);
+        let text = || value.unwrap_or_else(|| usage()).clone();
+        match flag {
+            "--runs" => self.runs = parse_u32(flag, value),
+            "--seed0" => self.seed0 = parse_u64(flag, value),
+            "--skip" => self.skip = parse_usize(flag, value),
+            "--take" => self.take = Some(parse_usize(flag, value)),
+            "--time-limit-s" => self.time_limit_s = parse_f64(flag, value),
+            "--decoder" => self.decoder = parse_decoder(flag, value),
+            "--objective" => self.objective = parse_objective(flag, value),
+            "--algo" => self.algo_name = text().to_ascii_lowercase(),
+            "--cooling" => self.cooling = parse_cooling(flag, value),
+            "--format" => self.format = parse_format(flag, value),
+            "--csv" => self.csv_path = Some(text()),
+            "--latex" => self.latex_path = Some(text()),
+            "--caption" => self.latex.caption = text(),
+            "--label" => self.latex.label = Some(text()),
+            "--columns" => self.latex.columns = parse_columns(flag, value),
+            "--latex-style" => self.latex.longtable = parse_latex_style(flag, value),
+            "--known" => self.known = Some(text()),
+            "--jobs" => self.jobs = parse_jobs(flag, value),
+            "--reduce" => {
+                self.reduce = true;
+                return Some(1);
+            }
+            "--progress" => {
+                self.progress = true;
+                return Some(1);
+            }
+            _ => unreachable!("flag {flag} is listed in a group but not handled"),
+        }
+        Some(2)
+    }
+
+    fn solver(&self) -> Box<dyn Solver> {
+        // Its walks are threads already, and scheduling would decide the per-run results.
+        if self.jobs > 1 && self.algo_name.starts_with("multi-tabu") {
+            eprintln!("--algo multi-tabu runs its own threads and cannot be combined with --jobs above 1");
+            usage()
+        }
+        let params = base_params(self.time_limit_s, self.decoder, self.objective);
+        build_solver(&self.algo_name, params, self.cooling, self.reduce)
+    }
+
+    fn select(&self, instances: Vec<Instance>) -> Vec<Instance> {
+        instances.into_iter().skip(self.skip).take(self.take.unwrap_or(usize::MAX)).collect()
+    }
+
+    // Writes the requested CSV and LaTeX files, then prints the table or the JSON array.
+    fn write_results<'a, S>(
+        &self,
+        source: &str,
+        rows: &[S],
+        records: impl IntoIterator<Item = &'a RunRecord>,
+        latex: fn(&[S], &LatexOptions) -> String,
+        table: fn(&[S]) -> String,
+    ) -> i32
+    where
+        S: ToJson,
+    {
+        if let Some(path) = &self.csv_path {
+            if let Err(e) = write_csv(path, records) {
+                eprintln!("writing {path} failed: {e}");
+                return 1;
+            }
+        }
+        if let Some(path) = &self.latex_path {
+            let mut opts = self.latex.clone();
+            if opts.caption.is_empty() {
+                opts.caption = default_caption(source, rows.len(), self.runs);
+            }
+            if let Err(e) = std::fs::write(path, latex(rows, &opts)) {
+                eprintln!("writing {path} failed: {e}");
+                return 1;
+            }
+        }
+        match self.format {
+            OutputFormat::Text => print!("{}", table(rows)),
+            OutputFormat::Json => println!("{}", rows.to_json()),
+        }
+        0
+    }
+}
+
+// The search settings the experiment commands run with; a limit <= 0 means none.
+fn base_params(time_limit_s: f64, decoder: DecoderKind, objective: Objective) -> TabuParams {
+    let time_limit = if time_limit_s <= 0.0 {
+        None
+    } else {
+        Some(Duration::from_secs_f64(time_limit_s))
+    };
+    TabuParams {
+        time_limit,
+        decoder,
+        objective,
+        ..TabuParams::default()
+    }
+}
+
+fn run_example(args: &[String]) -> i32 {
+    let mut format = OutputFormat::Text;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" => {
+                format = parse_format("--format", args.get(i + 1));
+                i += 2;
+            }
+            _ => usage(),
+        }
+    }
+
     let inst = example_instance_tp2();
     let params = TabuParams {
         max_iters: 2_000,
         neighborhood_samples: 150,
         tabu_tenure: 20,
         stagnation_limit: 400,
-        time_limit: None,
+        ..TabuParams::default()
     };
 
     let exact = exact_min_bins(&inst).ok();
     let res = tabu_search(&inst, 0, params);
 
+    if format == OutputFormat::Json {
+        if let Err(e) = validate_packing(&inst, &res.best_packing) {
+            eprintln!("ERROR: produced invalid packing: {e}");
+            return 1;
+        }
+        println!("{}", res.to_json());
+        return 0;
+    }
+
     println!(
         "Instance: {} (capacity={}, n={})",
         inst.name,
@@ -69,11 +416,12 @@ fn run_example() -> i32 {
         println!("Exact optimum (small-instance check): {opt} bins");
     }
     println!(
-        "Best found: {} bins (unused={})  iters={}  time(s)={:.4}",
+        "Best found: {} bins (unused={})  iters={}  time(s)={:.4}  iters/s={:.1}",
         res.best_bins,
         res.best_unused,
         res.iters,
-        res.elapsed.as_secs_f64()
+        res.elapsed.as_secs_f64(),
+        res.iters_per_sec
     );
 
     if let Err(e) = validate_packing(&inst, &res.best_packing) {
@@ -104,74 +452,24 @@ fn compare_exact_file(args: &[String]) -> i32 {
         usage();
     }
     let file = args[0].clone();
-    let mut runs: u32 = 5;
-    let mut seed0: u64 = 0;
-    let mut skip: usize = 0;
-    let mut take: Option<usize> = None;
-    let mut time_limit_s: f64 = 2.0;
-
-    let mut i = 1;
-    while i < args.len() {
-        match args[i].as_str() {
-            "--runs" => {
-                runs = parse_u32("--runs", args.get(i + 1));
-                i += 2;
-            }
-            "--seed0" => {
-                seed0 = parse_u64("--seed0", args.get(i + 1));
-                i += 2;
-            }
-            "--skip" => {
-                skip = parse_usize("--skip", args.get(i + 1));
-                i += 2;
-            }
-            "--take" => {
-                take = Some(parse_usize("--take", args.get(i + 1)));
-                i += 2;
-            }
-            "--time-limit-s" => {
-                time_limit_s = parse_f64("--time-limit-s", args.get(i + 1));
-                i += 2;
-            }
-            "--help" | "-h" => usage(),
-            other => {
-                eprintln!("Unknown arg: {other}");
-                usage()
-            }
-        }
-    }
+    let opts = CommonOpts::parse(&args[1..], &[SOLVER_FLAGS, RUN_FLAGS, SLICE_FLAGS, KNOWN_FLAGS]);
 
-    let instances = match load_bpp_instances_from_file(&file) {
+    let mut instances = match load_bpp_instances_from_file(&file) {
         Ok(v) => v,
         Err(e) => {
             eprintln!("{e}");
             return 1;
         }
     };
-
-    let time_limit = if time_limit_s <= 0.0 {
-        None
-    } else {
-        Some(Duration::from_secs_f64(time_limit_s))
-    };
-
-    let params = TabuParams {
-        max_iters: 5_000,
-        neighborhood_samples: 200,
-        tabu_tenure: 25,
-        stagnation_limit: 600,
-        time_limit,
-    };
-
-    let iter0 = instances.into_iter().skip(skip);
-    let iter: Box<dyn Iterator<Item = _>> = match take {
-        Some(k) => Box::new(iter0.take(k)),
-        None => Box::new(iter0),
-    };
+    if let Err(e) = apply_known(opts.known.as_deref(), &mut instances) {
+        eprintln!("{e}");
+        return 1;
+    }
+    let solver = opts.solver();
 
     let mut stdout = std::io::stdout().lock();
-    for inst in iter {
-        if let Err(e) = compare_against_exact(&inst, runs, seed0, params, &mut stdout) {
+    for inst in opts.select(instances) {
+        if let Err(e) = compare_against_exact(&inst, opts.runs, opts.seed0, solver.as_ref(), &mut stdout) {
             eprintln!("compare failed: {e}");
             return 1;
         }
@@ -184,44 +482,53 @@ fn report_file(args: &[String]) -> i32 {
         usage();
     }
     let file = args[0].clone();
-    let mut runs: u32 = 5;
-    let mut seed0: u64 = 0;
-    let mut skip: usize = 0;
-    let mut take: Option<usize> = None;
-    let mut time_limit_s: f64 = 2.0;
-    let mut progress = false;
+    let opts = CommonOpts::parse(
+        &args[1..],
+        &[SOLVER_FLAGS, RUN_FLAGS, PARALLEL_FLAGS, SLICE_FLAGS, OUTPUT_FLAGS, KNOWN_FLAGS],
+    );
+
+    let mut instances = match load_bpp_instances_from_file(&file) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
+    };
+    if let Err(e) = apply_known(opts.known.as_deref(), &mut instances) {
+        eprintln!("{e}");
+        return 1;
+    }
+    let solver = opts.solver();
+
+    let instances = opts.select(instances);
+    let stderr = Mutex::new(std::io::stderr());
+    let progress = opts.progress.then_some(&stderr as _);
+    let rows = run_instances_with_exact(&instances, opts.runs, opts.seed0, solver.as_ref(), opts.jobs, progress);
+    opts.write_results(&file, &rows, rows.iter().flat_map(|s| &s.runs), format_exact_gap_latex, format_exact_gap_table)
+}
+
+fn trace_tp2(args: &[String]) -> i32 {
+    trace(example_instance_tp2(), args)
+}
+
+fn trace_file(args: &[String]) -> i32 {
+    if args.is_empty() {
+        usage();
+    }
+    let file = args[0].clone();
+    let mut index: usize = 0;
+    let mut rest = Vec::new();
 
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
-            "--runs" => {
-                runs = parse_u32("--runs", args.get(i + 1));
-                i += 2;
-            }
-            "--seed0" => {
-                seed0 = parse_u64("--seed0", args.get(i + 1));
-                i += 2;
-            }
-            "--skip" => {
-                skip = parse_usize("--skip", args.get(i + 1));
-                i += 2;
-            }
-            "--take" => {
-                take = Some(parse_usize("--take", args.get(i + 1)));
-                i += 2;
-            }
-            "--time-limit-s" => {
-                time_limit_s = parse_f64("--time-limit-s", args.get(i + 1));
+            "--index" => {
+                index = parse_usize("--index", args.get(i + 1));
                 i += 2;
             }
-            "--progress" => {
-                progress = true;
-                i += 1;
-            }
-            "--help" | "-h" => usage(),
             other => {
-                eprintln!("Unknown arg: {other}");
-                usage()
+                rest.push(other.to_string());
+                i += 1;
             }
         }
     }
@@ -233,48 +540,22 @@ fn report_file(args: &[String]) -> i32 {
             return 1;
         }
     };
-
-    let time_limit = if time_limit_s <= 0.0 {
-        None
-    } else {
-        Some(Duration::from_secs_f64(time_limit_s))
-    };
-
-    let params = TabuParams {
-        max_iters: 5_000,
-        neighborhood_samples: 200,
-        tabu_tenure: 25,
-        stagnation_limit: 600,
-        time_limit,
-    };
-
-    let iter0 = instances.into_iter().skip(skip);
-    let iter: Box<dyn Iterator<Item = _>> = match take {
-        Some(k) => Box::new(iter0.take(k)),
-        None => Box::new(iter0),
+    let Some(inst) = instances.into_iter().nth(index) else {
+        eprintln!("{file}: no instance at index {index}");
+        return 1;
     };
-
-    let mut rows = Vec::new();
-    let mut stderr = std::io::stderr().lock();
-    for inst in iter {
-        let row = if progress {
-            run_instance_with_exact_verbose(&inst, runs, seed0, params, &mut stderr)
-        } else {
-            run_instance_with_exact(&inst, runs, seed0, params)
-        };
-        rows.push(row);
-    }
-    print!("{}", format_exact_gap_table(&rows));
-    0
+    trace(inst, &rest)
 }
 
-fn trace_tp2(args: &[String]) -> i32 {
+fn trace(inst: Instance, args: &[String]) -> i32 {
     let mut iters: u32 = 30;
     let mut samples: u32 = 25;
     let mut tenure: usize = 10;
     let mut seed: u64 = 0;
     let mut show_packings = false;
     let mut show_candidates = true;
+    let mut decoder = DecoderKind::BestFit;
+    let mut objective = Objective::default();
 
     let mut i = 0;
     while i < args.len() {
@@ -303,6 +584,14 @@ fn trace_tp2(args: &[String]) -> i32 {
                 show_candidates = false;
                 i += 1;
             }
+            "--decoder" => {
+                decoder = parse_decoder("--decoder", args.get(i + 1));
+                i += 2;
+            }
+            "--objective" => {
+                objective = parse_objective("--objective", args.get(i + 1));
+                i += 2;
+            }
             "--help" | "-h" => usage(),
             other => {
                 eprintln!("Unknown arg: {other}");
@@ -311,13 +600,14 @@ fn trace_tp2(args: &[String]) -> i32 {
         }
     }
 
-    let inst = example_instance_tp2();
     let params = TabuParams {
         max_iters: iters,
         neighborhood_samples: samples,
         tabu_tenure: tenure,
         stagnation_limit: 10_000,
-        time_limit: None,
+        decoder,
+        objective,
+        ..TabuParams::default()
     };
     let cfg = TraceConfig {
         show_candidates,
@@ -333,29 +623,113 @@ fn trace_tp2(args: &[String]) -> i32 {
 }
 
 fn run_batch(args: &[String]) -> i32 {
-    let mut runs: u32 = 5;
-    let mut seed0: u64 = 0;
-    let mut time_limit_s: f64 = 2.0;
-    let mut progress = false;
+    let opts = CommonOpts::parse(args, &[SOLVER_FLAGS, RUN_FLAGS, PARALLEL_FLAGS, OUTPUT_FLAGS]);
+    let solver = opts.solver();
 
-    let mut i = 0;
+    let stderr = Mutex::new(std::io::stderr());
+    let progress = opts.progress.then_some(&stderr as _);
+    let summaries = run_instances(&default_batch_instances(), opts.runs, opts.seed0, solver.as_ref(), opts.jobs, progress);
+    opts.write_results("default batch", &summaries, summaries.iter().flat_map(|s| &s.runs), format_latex, format_table)
+}
+
+fn report_batch(args: &[String]) -> i32 {
+    let opts = CommonOpts::parse(args, &[SOLVER_FLAGS, RUN_FLAGS, PARALLEL_FLAGS, OUTPUT_FLAGS, KNOWN_FLAGS]);
+    let solver = opts.solver();
+
+    let mut instances = default_batch_instances();
+    if let Err(e) = apply_known(opts.known.as_deref(), &mut instances) {
+        eprintln!("{e}");
+        return 1;
+    }
+
+    let stderr = Mutex::new(std::io::stderr());
+    let progress = opts.progress.then_some(&stderr as _);
+    let rows = run_instances_with_exact(&instances, opts.runs, opts.seed0, solver.as_ref(), opts.jobs, progress);
+    opts.write_results("default batch", &rows, rows.iter().flat_map(|s| &s.runs), format_exact_gap_latex, format_exact_gap_table)
+}
+
+fn run_dir(args: &[String]) -> i32 {
+    if args.is_empty() {
+        usage();
+    }
+    let dir = args[0].clone();
+    let opts = CommonOpts::parse(&args[1..], &[SOLVER_FLAGS, RUN_FLAGS, PARALLEL_FLAGS, SLICE_FLAGS, OUTPUT_FLAGS]);
+
+    let instances = match load_bpp_instances_from_dir(&dir) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
+    };
+    let solver = opts.solver();
+
+    let instances = opts.select(instances);
+    let stderr = Mutex::new(std::io::stderr());
+    let progress = opts.progress.then_some(&stderr as _);
+    let summaries = run_instances(&instances, opts.runs, opts.seed0, solver.as_ref(), opts.jobs, progress);
+    opts.write_results(&dir, &summaries, summaries.iter().flat_map(|s| &s.runs), format_latex, format_table)
+}
+
+fn run_file(args: &[String]) -> i32 {
+    if args.is_empty() {
+        usage();
+    }
+    let file = args[0].clone();
+    let opts = CommonOpts::parse(&args[1..], &[SOLVER_FLAGS, RUN_FLAGS, PARALLEL_FLAGS, SLICE_FLAGS, OUTPUT_FLAGS]);
+
+    let instances = match load_bpp_instances_from_file(&file) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
+    };
+    let solver = opts.solver();
+
+    let instances = opts.select(instances);
+    let stderr = Mutex::new(std::io::stderr());
+    let progress = opts.progress.then_some(&stderr as _);
+    let summaries = run_instances(&instances, opts.runs, opts.seed0, solver.as_ref(), opts.jobs, progress);
+    opts.write_results(&file, &summaries, summaries.iter().flat_map(|s| &s.runs), format_latex, format_table)
+}
+
+fn export_mip(args: &[String]) -> i32 {
+    if args.is_empty() {
+        usage();
+    }
+    let file = args[0].clone();
+    let mut model = MipModel::Assignment;
+    let mut format = MipFormat::Lp;
+    let mut index: usize = 0;
+    let mut out_path: Option<String> = None;
+
+    let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
-            "--runs" => {
-                runs = parse_u32("--runs", args.get(i + 1));
+            "--model" => {
+                let name = args.get(i + 1).unwrap_or_else(|| usage());
+                model = MipModel::parse(name).unwrap_or_else(|| {
+                    eprintln!("Invalid value for --model: {name} (expected assignment or arcflow)");
+                    usage()
+                });
                 i += 2;
             }
-            "--seed0" => {
-                seed0 = parse_u64("--seed0", args.get(i + 1));
+            "--format" => {
+                let name = args.get(i + 1).unwrap_or_else(|| usage());
+                format = MipFormat::parse(name).unwrap_or_else(|| {
+                    eprintln!("Invalid value for --format: {name} (expected lp or mps)");
+                    usage()
+                });
                 i += 2;
             }
-            "--time-limit-s" => {
-                time_limit_s = parse_f64("--time-limit-s", args.get(i + 1));
+            "--index" => {
+                index = parse_usize("--index", args.get(i + 1));
                 i += 2;
             }
-            "--progress" => {
-                progress = true;
-                i += 1;
+            "--out" => {
+                out_path = Some(args.get(i + 1).unwrap_or_else(|| usage()).clone());
+                i += 2;
             }
             "--help" | "-h" => usage(),
             other => {
@@ -365,59 +739,64 @@ fn run_batch(args: &[String]) -> i32 {
         }
     }
 
-    let time_limit = if time_limit_s <= 0.0 {
-        None
-    } else {
-        Some(Duration::from_secs_f64(time_limit_s))
+    let instances = match load_bpp_instances_from_file(&file) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
     };
-
-    let params = TabuParams {
-        max_iters: 5_000,
-        neighborhood_samples: 200,
-        tabu_tenure: 25,
-        stagnation_limit: 600,
-        time_limit,
+    let Some(inst) = instances.into_iter().nth(index) else {
+        eprintln!("{file}: no instance at index {index}");
+        return 1;
     };
 
-    let mut summaries = Vec::new();
-    for inst in default_batch_instances() {
-        let (s, _, _) = if progress {
-            let mut stderr = std::io::stderr().lock();
-            run_instance_verbose(&inst, runs, seed0, params, &mut stderr)
-        } else {
-            run_instance(&inst, runs, seed0, params)
-        };
-        summaries.push(s);
+    let written = match &out_path {
+        Some(path) => std::fs::File::create(path)
+            .map(std::io::BufWriter::new)
+            .and_then(|mut w| write_mip(&inst, model, format, &mut w)),
+        None => write_mip(&inst, model, format, &mut std::io::stdout().lock()),
+    };
+    if let Err(e) = written {
+        eprintln!("export failed: {e}");
+        return 1;
     }
-
-    print!("{}", format_table(&summaries));
     0
 }
 
-fn report_batch(args: &[String]) -> i32 {
-    let mut runs: u32 = 5;
-    let mut seed0: u64 = 0;
-    let mut time_limit_s: f64 = 2.0;
-    let mut progress = false;
+fn generate(args: &[String]) -> i32 {
+    if args.is_empty() {
+        usage();
+    }
+    let class = args[0].to_ascii_lowercase();
+    let mut items: Option<usize> = None;
+    let mut count: Option<usize> = None;
+    let mut seed: u64 = 0;
+    let mut out_path: Option<String> = None;
+    let mut dir: Option<String> = None;
 
-    let mut i = 0;
+    let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
-            "--runs" => {
-                runs = parse_u32("--runs", args.get(i + 1));
+            "--items" => {
+                items = Some(parse_usize("--items", args.get(i + 1)));
                 i += 2;
             }
-            "--seed0" => {
-                seed0 = parse_u64("--seed0", args.get(i + 1));
+            "--count" => {
+                count = Some(parse_usize("--count", args.get(i + 1)));
                 i += 2;
             }
-            "--time-limit-s" => {
-                time_limit_s = parse_f64("--time-limit-s", args.get(i + 1));
+            "--seed" => {
+                seed = parse_u64("--seed", args.get(i + 1));
                 i += 2;
             }
-            "--progress" => {
-                progress = true;
-                i += 1;
+            "--out" => {
+                out_path = Some(args.get(i + 1).unwrap_or_else(|| usage()).clone());
+                i += 2;
+            }
+            "--dir" => {
+                dir = Some(args.get(i + 1).unwrap_or_else(|| usage()).clone());
+                i += 2;
             }
             "--help" | "-h" => usage(),
             other => {
@@ -427,162 +806,143 @@ fn report_batch(args: &[String]) -> i32 {
         }
     }
 
-    let time_limit = if time_limit_s <= 0.0 {
-        None
-    } else {
-        Some(Duration::from_secs_f64(time_limit_s))
+    let generated = match (class.as_str(), items) {
+        ("triplets", Some(items)) => triplet_class(items, count.unwrap_or(20), seed),
+        (_, Some(_)) => Err("--items only applies to the triplets class".to_string()),
+        (class, None) => generate_class(class, count, seed),
     };
-
-    let params = TabuParams {
-        max_iters: 5_000,
-        neighborhood_samples: 200,
-        tabu_tenure: 25,
-        stagnation_limit: 600,
-        time_limit,
+    let instances = match generated {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
     };
 
-    let mut summaries = Vec::new();
-    for inst in default_batch_instances() {
-        let s = if progress {
-            let mut stderr = std::io::stderr().lock();
-            run_instance_with_exact_verbose(&inst, runs, seed0, params, &mut stderr)
-        } else {
-            run_instance_with_exact(&inst, runs, seed0, params)
-        };
-        summaries.push(s);
+    if let Some(dir) = &dir {
+        if let Err(e) = write_instance_dir(&instances, dir) {
+            eprintln!("{e}");
+            return 1;
+        }
+        eprintln!("wrote {} instances to {dir}", instances.len());
+        return 0;
+    }
+    let written = match &out_path {
+        Some(path) => std::fs::File::create(path)
+            .map(std::io::BufWriter::new)
+            .and_then(|mut w| write_binpack_multi(&instances, &mut w).and_then(|_| w.flush())),
+        None => write_binpack_multi(&instances, &mut std::io::stdout().lock()),
+    };
+    if let Err(e) = written {
+        eprintln!("writing instances failed: {e}");
+        return 1;
     }
-
-    print!("{}", format_exact_gap_table(&summaries));
     0
 }
 
-fn run_dir(args: &[String]) -> i32 {
+fn solve(args: &[String]) -> i32 {
     if args.is_empty() {
         usage();
     }
-    let dir = args[0].clone();
-    let mut runs: u32 = 5;
-    let mut seed0: u64 = 0;
-    let mut skip: usize = 0;
-    let mut take: Option<usize> = None;
-    let mut time_limit_s: f64 = 2.0;
-    let mut progress = false;
+    let file = args[0].clone();
+    let mut opts = CommonOpts::default();
+    let mut index: usize = 0;
+    let mut seed: u64 = 0;
+    let mut out_path: Option<String> = None;
+    let mut patterns = false;
 
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
-            "--runs" => {
-                runs = parse_u32("--runs", args.get(i + 1));
-                i += 2;
-            }
-            "--seed0" => {
-                seed0 = parse_u64("--seed0", args.get(i + 1));
+            "--index" => {
+                index = parse_usize("--index", args.get(i + 1));
                 i += 2;
             }
-            "--skip" => {
-                skip = parse_usize("--skip", args.get(i + 1));
-                i += 2;
-            }
-            "--take" => {
-                take = Some(parse_usize("--take", args.get(i + 1)));
+            "--seed" => {
+                seed = parse_u64("--seed", args.get(i + 1));
                 i += 2;
             }
-            "--time-limit-s" => {
-                time_limit_s = parse_f64("--time-limit-s", args.get(i + 1));
+            "--out" => {
+                out_path = Some(args.get(i + 1).unwrap_or_else(|| usage()).clone());
                 i += 2;
             }
-            "--progress" => {
-                progress = true;
+            "--patterns" => {
+                patterns = true;
                 i += 1;
             }
-            "--help" | "-h" => usage(),
-            other => {
-                eprintln!("Unknown arg: {other}");
-                usage()
-            }
+            other => match opts.parse_flag(args, i, &[SOLVER_FLAGS]) {
+                Some(used) => i += used,
+                None => {
+                    eprintln!("Unknown arg: {other}");
+                    usage()
+                }
+            },
         }
     }
 
-    let instances = match load_bpp_instances_from_dir(&dir) {
+    let instances = match load_bpp_instances_from_file(&file) {
         Ok(v) => v,
         Err(e) => {
             eprintln!("{e}");
             return 1;
         }
     };
-
-    let time_limit = if time_limit_s <= 0.0 {
-        None
-    } else {
-        Some(Duration::from_secs_f64(time_limit_s))
+    let Some(inst) = instances.into_iter().nth(index) else {
+        eprintln!("{file}: no instance at index {index}");
+        return 1;
     };
 
-    let params = TabuParams {
-        max_iters: 5_000,
-        neighborhood_samples: 200,
-        tabu_tenure: 25,
-        stagnation_limit: 600,
-        time_limit,
-    };
+    let solver = opts.solver();
+    let res = solver.solve(&inst, seed);
+    if let Err(e) = validate_packing(&inst, &res.best_packing) {
+        eprintln!("ERROR: produced invalid packing: {e}");
+        return 1;
+    }
+    eprintln!(
+        "instance={} solver={} bins={} unused={} time(s)={:.4} iters={}",
+        inst.name,
+        solver.name(),
+        res.best_bins,
+        res.best_unused,
+        res.elapsed.as_secs_f64(),
+        res.iters
+    );
 
-    let mut summaries = Vec::new();
-    let iter0 = instances.into_iter().skip(skip);
-    let iter: Box<dyn Iterator<Item = _>> = match take {
-        Some(k) => Box::new(iter0.take(k)),
-        None => Box::new(iter0),
-    };
-    let mut stderr = std::io::stderr().lock();
-    for inst in iter {
-        let (s, _, _) = if progress {
-            run_instance_verbose(&inst, runs, seed0, params, &mut stderr)
+    let write = |mut w: &mut dyn Write| {
+        if patterns {
+            write_patterns(&inst, &cutting_patterns(&inst, &res.best_packing), &mut w)
         } else {
-            run_instance(&inst, runs, seed0, params)
-        };
-        summaries.push(s);
+            write_solution(&inst, &res.best_packing, &mut w)
+        }
+    };
+    let written = match &out_path {
+        Some(path) => std::fs::File::create(path)
+            .map(std::io::BufWriter::new)
+            .and_then(|mut w| write(&mut w).and_then(|_| w.flush())),
+        None => write(&mut std::io::stdout().lock()),
+    };
+    if let Err(e) = written {
+        eprintln!("writing solution failed: {e}");
+        return 1;
     }
-    print!("{}", format_table(&summaries));
     0
 }
 
-fn run_file(args: &[String]) -> i32 {
-    if args.is_empty() {
+fn validate(args: &[String]) -> i32 {
+    if args.len() < 2 {
         usage();
     }
     let file = args[0].clone();
-    let mut runs: u32 = 5;
-    let mut seed0: u64 = 0;
-    let mut skip: usize = 0;
-    let mut take: Option<usize> = None;
-    let mut time_limit_s: f64 = 2.0;
-    let mut progress = false;
+    let sol_path = args[1].clone();
+    let mut index: Option<usize> = None;
 
-    let mut i = 1;
+    let mut i = 2;
     while i < args.len() {
         match args[i].as_str() {
-            "--runs" => {
-                runs = parse_u32("--runs", args.get(i + 1));
-                i += 2;
-            }
-            "--seed0" => {
-                seed0 = parse_u64("--seed0", args.get(i + 1));
-                i += 2;
-            }
-            "--skip" => {
-                skip = parse_usize("--skip", args.get(i + 1));
-                i += 2;
-            }
-            "--take" => {
-                take = Some(parse_usize("--take", args.get(i + 1)));
-                i += 2;
-            }
-            "--time-limit-s" => {
-                time_limit_s = parse_f64("--time-limit-s", args.get(i + 1));
+            "--index" => {
+                index = Some(parse_usize("--index", args.get(i + 1)));
                 i += 2;
             }
-            "--progress" => {
-                progress = true;
-                i += 1;
-            }
             "--help" | "-h" => usage(),
             other => {
                 eprintln!("Unknown arg: {other}");
@@ -598,38 +958,40 @@ fn run_file(args: &[String]) -> i32 {
             return 1;
         }
     };
-
-    let time_limit = if time_limit_s <= 0.0 {
-        None
-    } else {
-        Some(Duration::from_secs_f64(time_limit_s))
+    let sol = match read_solution(&sol_path) {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("{e}");
+            return 1;
+        }
     };
 
-    let params = TabuParams {
-        max_iters: 5_000,
-        neighborhood_samples: 200,
-        tabu_tenure: 25,
-        stagnation_limit: 600,
-        time_limit,
+    // The instance named in the solution, unless --index picks one.
+    let found = match index {
+        Some(k) => instances.get(k),
+        None => instances
+            .iter()
+            .find(|inst| inst.name.trim() == sol.instance_name)
+            .or(if instances.len() == 1 { instances.first() } else { None }),
     };
-
-    let mut summaries = Vec::new();
-    let iter0 = instances.into_iter().skip(skip);
-    let iter: Box<dyn Iterator<Item = _>> = match take {
-        Some(k) => Box::new(iter0.take(k)),
-        None => Box::new(iter0),
+    let Some(inst) = found else {
+        eprintln!("{file}: no instance matches `{}` (use --index)", sol.instance_name);
+        return 1;
     };
-    let mut stderr = std::io::stderr().lock();
-    for inst in iter {
-        let (s, _, _) = if progress {
-            run_instance_verbose(&inst, runs, seed0, params, &mut stderr)
-        } else {
-            run_instance(&inst, runs, seed0, params)
-        };
-        summaries.push(s);
+    if inst.name.trim() != sol.instance_name {
+        println!("note: solution is for `{}`, checking against `{}`", sol.instance_name, inst.name);
     }
-    print!("{}", format_table(&summaries));
-    0
+
+    let violations = packing_violations(inst, &sol.packing);
+    if violations.is_empty() {
+        println!("OK: {} bins, feasible packing of {} ({} items)", sol.packing.n_bins(), inst.name, inst.sizes.len());
+        return 0;
+    }
+    println!("INVALID: {} violation(s) in {sol_path}", violations.len());
+    for v in &violations {
+        println!("  {v}");
+    }
+    1
 }
 
 fn main() {
@@ -639,14 +1001,19 @@ fn main() {
     }
     let cmd = args[1].as_str();
     let code = match cmd {
-        "run-example" => run_example(),
+        "run-example" => run_example(&args[2..]),
         "trace-tp2" => trace_tp2(&args[2..]),
+        "trace-file" => trace_file(&args[2..]),
         "compare-exact-file" => compare_exact_file(&args[2..]),
         "report-file" => report_file(&args[2..]),
         "report-batch" => report_batch(&args[2..]),
         "run-batch" => run_batch(&args[2..]),
         "run-file" => run_file(&args[2..]),
         "run-dir" => run_dir(&args[2..]),
+        "export-mip" => export_mip(&args[2..]),
+        "generate" => generate(&args[2..]),
+        "solve" => solve(&args[2..]),
+        "validate" => validate(&args[2..]),
         _ => usage(),
     };
     std::process::exit(code);
@@ -0,0 +1,374 @@
+use std::io::Write;
+
+use crate::instances::Instance;
+use crate::packing::{try_reduce_bins, BestFit, Decoder};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MipModel {
+    // x_i_b = item i in bin b, y_b = bin b used, over as many bins as best fit decreasing needs.
+    Assignment,
+    // Valerio de Carvalho's arc-flow model: z units of flow from 0 to C, one arc per item size.
+    ArcFlow,
+}
+
+impl MipModel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MipModel::Assignment => "assignment",
+            MipModel::ArcFlow => "arcflow",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "assignment" => Some(MipModel::Assignment),
+            "arcflow" | "arc-flow" => Some(MipModel::ArcFlow),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MipFormat {
+    Lp,
+    Mps,
+}
+
+impl MipFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "lp" => Some(MipFormat::Lp),
+            "mps" => Some(MipFormat::Mps),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Sense {
+    Le,
+    Ge,
+    Eq,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum VarKind {
+    Binary,
+    Integer,
+}
+
+struct Row {
+    name: String,
+    terms: Vec<(usize, i64)>,
+    sense: Sense,
+    rhs: i64,
+}
+
+// A minimisation MIP with integer coefficients, in the shape both writers need.
+struct Model {
+    name: String,
+    vars: Vec<(String, VarKind)>,
+    objective: Vec<(usize, i64)>,
+    rows: Vec<Row>,
+}
+
+impl Model {
+    fn var(&mut self, name: String, kind: VarKind) -> usize {
+        self.vars.push((name, kind));
+        self.vars.len() - 1
+    }
+}
+
+fn assignment_model(instance: &Instance) -> Model {
+    let n = instance.sizes.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(instance.sizes[i]));
+    let n_bins = try_reduce_bins(instance, &BestFit.pack(instance, &order)).n_bins();
+
+    let mut model = Model {
+        name: instance.name.clone(),
+        vars: Vec::new(),
+        objective: Vec::new(),
+        rows: Vec::new(),
+    };
+    let y: Vec<usize> = (0..n_bins)
+        .map(|b| model.var(format!("y_{}", b + 1), VarKind::Binary))
+        .collect();
+    let x: Vec<Vec<usize>> = (0..n)
+        .map(|i| {
+            (0..n_bins)
+                .map(|b| model.var(format!("x_{}_{}", i + 1, b + 1), VarKind::Binary))
+                .collect()
+        })
+        .collect();
+
+    model.objective = y.iter().map(|&v| (v, 1)).collect();
+    for (i, xi) in x.iter().enumerate() {
+        model.rows.push(Row {
+            name: format!("assign_{}", i + 1),
+            terms: xi.iter().map(|&v| (v, 1)).collect(),
+            sense: Sense::Eq,
+            rhs: 1,
+        });
+    }
+    for b in 0..n_bins {
+        let mut terms: Vec<(usize, i64)> = (0..n).map(|i| (x[i][b], instance.sizes[i] as i64)).collect();
+        terms.push((y[b], -(instance.capacity as i64)));
+        model.rows.push(Row {
+            name: format!("cap_{}", b + 1),
+            terms,
+            sense: Sense::Le,
+            rhs: 0,
+        });
+    }
+    // Symmetry breaking: bins are used in order.
+    for b in 1..n_bins {
+        model.rows.push(Row {
+            name: format!("order_{}", b + 1),
+            terms: vec![(y[b - 1], 1), (y[b], -1)],
+            sense: Sense::Ge,
+            rhs: 0,
+        });
+    }
+    model
+}
+
+fn arcflow_model(instance: &Instance) -> Model {
+    let cap = instance.capacity as usize;
+    let mut sizes = instance.sizes.clone();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    let mut demand: Vec<(usize, i64)> = Vec::new();
+    for s in sizes {
+        match demand.last_mut() {
+            Some((w, d)) if *w == s as usize => *d += 1,
+            _ => demand.push((s as usize, 1)),
+        }
+    }
+
+    let mut model = Model {
+        name: instance.name.clone(),
+        vars: Vec::new(),
+        objective: Vec::new(),
+        rows: Vec::new(),
+    };
+    let z = model.var("z".to_string(), VarKind::Integer);
+    model.objective = vec![(z, 1)];
+
+    // Item arcs in decreasing size order: an arc of size w only leaves nodes reached by items
+    // of size >= w, and at most d_w of them are chained (the usual arc-flow graph reduction).
+    let mut reached = vec![false; cap + 1];
+    reached[0] = true;
+    let mut arcs: Vec<(usize, usize, usize)> = Vec::new();
+    let mut by_size: Vec<Vec<usize>> = Vec::new();
+    for &(w, d) in &demand {
+        let mut copies: Vec<Option<i64>> = reached.iter().map(|&r| r.then_some(0)).collect();
+        let mut mine = Vec::new();
+        for v in 0..=cap.saturating_sub(w) {
+            if let Some(k) = copies[v] {
+                if k < d {
+                    let var = model.var(format!("f_{}_{}", v, v + w), VarKind::Integer);
+                    arcs.push((v, v + w, var));
+                    mine.push(var);
+                    copies[v + w] = Some(copies[v + w].map_or(k + 1, |c| c.min(k + 1)));
+                }
+            }
+        }
+        reached = copies.iter().map(|c| c.is_some()).collect();
+        by_size.push(mine);
+    }
+    // Loss arcs close a partly filled bin.
+    for (v, _) in reached.iter().enumerate().filter(|&(v, &r)| r && v > 0 && v < cap) {
+        let var = model.var(format!("l_{}", v), VarKind::Integer);
+        arcs.push((v, cap, var));
+    }
+
+    let mut node_terms: Vec<Vec<(usize, i64)>> = vec![Vec::new(); cap + 1];
+    for &(from, to, var) in &arcs {
+        node_terms[from].push((var, -1));
+        node_terms[to].push((var, 1));
+    }
+    for (v, mut terms) in node_terms.into_iter().enumerate().filter(|&(v, _)| reached[v]) {
+        if v == 0 {
+            terms.push((z, 1));
+        } else if v == cap {
+            terms.push((z, -1));
+        }
+        if !terms.is_empty() {
+            model.rows.push(Row {
+                name: format!("flow_{}", v),
+                terms,
+                sense: Sense::Eq,
+                rhs: 0,
+            });
+        }
+    }
+    for (&(w, d), vars) in demand.iter().zip(by_size.iter()) {
+        model.rows.push(Row {
+            name: format!("demand_{}", w),
+            terms: vars.iter().map(|&v| (v, 1)).collect(),
+            sense: Sense::Ge,
+            rhs: d,
+        });
+    }
+    model
+}
+
+fn build_model(instance: &Instance, model: MipModel) -> Model {
+    match model {
+        MipModel::Assignment => assignment_model(instance),
+        MipModel::ArcFlow => arcflow_model(instance),
+    }
+}
+
+// Names must not contain spaces or LP operators; instance names are used as given otherwise.
+pub(crate) fn sanitize(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '_' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+fn write_terms<W: Write>(out: &mut W, model: &Model, terms: &[(usize, i64)]) -> std::io::Result<()> {
+    for (k, &(var, coef)) in terms.iter().enumerate() {
+        let name = &model.vars[var].0;
+        let body = if coef.abs() == 1 {
+            name.clone()
+        } else {
+            format!("{} {}", coef.abs(), name)
+        };
+        if k == 0 {
+            write!(out, "{}{body}", if coef < 0 { "- " } else { "" })?;
+            continue;
+        }
+        if k % 8 == 0 {
+            write!(out, "\n  ")?;
+        }
+        write!(out, " {} {body}", if coef < 0 { "-" } else { "+" })?;
+    }
+    Ok(())
+}
+
+fn write_lp<W: Write>(model: &Model, out: &mut W) -> std::io::Result<()> {
+    writeln!(out, "\\ Problem: {}", sanitize(&model.name))?;
+    writeln!(out, "Minimize")?;
+    write!(out, " obj: ")?;
+    write_terms(out, model, &model.objective)?;
+    writeln!(out)?;
+    writeln!(out, "Subject To")?;
+    for row in &model.rows {
+        write!(out, " {}: ", row.name)?;
+        write_terms(out, model, &row.terms)?;
+        let op = match row.sense {
+            Sense::Le => "<=",
+            Sense::Ge => ">=",
+            Sense::Eq => "=",
+        };
+        writeln!(out, " {op} {}", row.rhs)?;
+    }
+    for (kind, header) in [(VarKind::Binary, "Binaries"), (VarKind::Integer, "Generals")] {
+        let names: Vec<&str> = model
+            .vars
+            .iter()
+            .filter(|(_, k)| *k == kind)
+            .map(|(n, _)| n.as_str())
+            .collect();
+        if names.is_empty() {
+            continue;
+        }
+        writeln!(out, "{header}")?;
+        for chunk in names.chunks(8) {
+            writeln!(out, " {}", chunk.join(" "))?;
+        }
+    }
+    writeln!(out, "End")
+}
+
+// Free MPS. Integer columns sit between INTORG/INTEND markers with explicit bounds, since
+// readers disagree on the default upper bound of marked columns.
+fn write_mps<W: Write>(model: &Model, out: &mut W) -> std::io::Result<()> {
+    writeln!(out, "NAME {}", sanitize(&model.name))?;
+    writeln!(out, "ROWS")?;
+    writeln!(out, " N obj")?;
+    for row in &model.rows {
+        let code = match row.sense {
+            Sense::Le => "L",
+            Sense::Ge => "G",
+            Sense::Eq => "E",
+        };
+        writeln!(out, " {code} {}", row.name)?;
+    }
+
+    let mut columns: Vec<Vec<(&str, i64)>> = vec![Vec::new(); model.vars.len()];
+    for &(var, coef) in &model.objective {
+        columns[var].push(("obj", coef));
+    }
+    for row in &model.rows {
+        for &(var, coef) in &row.terms {
+            columns[var].push((row.name.as_str(), coef));
+        }
+    }
+    writeln!(out, "COLUMNS")?;
+    writeln!(out, " MARKER 'MARKER' 'INTORG'")?;
+    for ((name, _), entries) in model.vars.iter().zip(columns.iter()) {
+        for (row, coef) in entries {
+            writeln!(out, " {name} {row} {coef}")?;
+        }
+    }
+    writeln!(out, " MARKER 'MARKER' 'INTEND'")?;
+
+    writeln!(out, "RHS")?;
+    for row in model.rows.iter().filter(|r| r.rhs != 0) {
+        writeln!(out, " RHS {} {}", row.name, row.rhs)?;
+    }
+    writeln!(out, "BOUNDS")?;
+    for (name, kind) in &model.vars {
+        match kind {
+            VarKind::Binary => writeln!(out, " BV BND {name}")?,
+            VarKind::Integer => writeln!(out, " PL BND {name}")?,
+        }
+    }
+    writeln!(out, "ENDATA")
+}
+
+pub fn write_mip<W: Write>(instance: &Instance, model: MipModel, format: MipFormat, out: &mut W) -> std::io::Result<()> {
+    let m = build_model(instance, model);
+    match format {
+        MipFormat::Lp => write_lp(&m, out),
+        MipFormat::Mps => write_mps(&m, out),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tiny() -> Instance {
+        Instance {
+            name: "tiny".to_string(),
+            capacity: 10,
+            sizes: vec![6, 4, 5, 5],
+            opt_bins: Some(2),
+            best_known: None,
+            known_opt: None,
+        }
+    }
+
+    fn export(model: MipModel, format: MipFormat) -> String {
+        let mut out = Vec::new();
+        write_mip(&tiny(), model, format, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn exports_match_golden_files() {
+        let cases = [
+            (MipModel::Assignment, MipFormat::Lp, include_str!("../testdata/tiny_assignment.lp")),
+            (MipModel::Assignment, MipFormat::Mps, include_str!("../testdata/tiny_assignment.mps")),
+            (MipModel::ArcFlow, MipFormat::Lp, include_str!("../testdata/tiny_arcflow.lp")),
+            (MipModel::ArcFlow, MipFormat::Mps, include_str!("../testdata/tiny_arcflow.mps")),
+        ];
+        for (model, format, golden) in cases {
+            assert_eq!(export(model, format), golden, "{} {:?}", model.name(), format);
+        }
+    }
+}
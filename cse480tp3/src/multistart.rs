@@ -0,0 +1,258 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::bounds::best_lower_bound;
+use crate::instances::Instance;
+use crate::packing::{packing_objective, try_reduce_bins, Packing, PrefixCache};
+use crate::rng::XorShift64;
+use crate::solver::Solver;
+use crate::tabu::{
+    decreasing_order, iters_per_sec, tabu_push, MoveKey, MoveSampler, NoopObserver, StopReason, TabuParams, TabuResult,
+};
+
+// Several permutation tabu walks in one solve call, one per thread, sharing a pool of elite
+// permutations. A walk that stagnates for `tabu.stagnation_limit` iterations restarts from a
+// perturbed elite instead of reshuffling its own best, and the first walk to reach the lower
+// bound stops the others. `tabu.max_iters` counts per walk; `tabu.space` is ignored. With more
+// than one thread the result depends on scheduling, so it is not reproducible from the seed.
+#[derive(Clone, Copy, Debug)]
+pub struct MultiStartParams {
+    pub tabu: TabuParams,
+    // 0 means one per available core.
+    pub threads: usize,
+    pub elite_size: usize,
+    // Random swaps applied to an elite before a walk restarts from it.
+    pub perturbation: usize,
+}
+
+impl Default for MultiStartParams {
+    fn default() -> Self {
+        Self {
+            tabu: TabuParams::default(),
+            threads: 0,
+            elite_size: 8,
+            perturbation: 5,
+        }
+    }
+}
+
+impl Solver for MultiStartParams {
+    fn name(&self) -> String {
+        "multi-tabu".to_string()
+    }
+
+    fn solve(&self, instance: &Instance, seed: u64) -> TabuResult {
+        multi_start_tabu(instance, seed, *self)
+    }
+}
+
+struct Elite {
+    order: Vec<usize>,
+    packing: Packing,
+    score: (usize, f64),
+}
+
+struct Shared {
+    // Distinct permutations, best first; the first one is the incumbent.
+    elite: Mutex<Vec<Elite>>,
+    capacity: usize,
+    stop: AtomicBool,
+}
+
+impl Shared {
+    fn offer(&self, order: &[usize], packing: &Packing, score: (usize, f64)) {
+        let mut elite = self.elite.lock().unwrap_or_else(|e| e.into_inner());
+        if elite.len() >= self.capacity && elite.last().is_some_and(|e| e.score <= score) {
+            return;
+        }
+        if elite.iter().any(|e| e.order == order) {
+            return;
+        }
+        let pos = elite.iter().position(|e| score < e.score).unwrap_or(elite.len());
+        elite.insert(
+            pos,
+            Elite {
+                order: order.to_vec(),
+                packing: packing.clone(),
+                score,
+            },
+        );
+        elite.truncate(self.capacity);
+    }
+
+    fn pick(&self, rng: &mut XorShift64) -> Option<Vec<usize>> {
+        let elite = self.elite.lock().unwrap_or_else(|e| e.into_inner());
+        if elite.is_empty() {
+            return None;
+        }
+        Some(elite[rng.gen_range_usize(elite.len())].order.clone())
+    }
+}
+
+struct WalkEnd {
+    iters: u32,
+    timed_out: bool,
+}
+
+fn walk(instance: &Instance, params: &MultiStartParams, lb: usize, seed: u64, start: Instant, shared: &Shared) -> WalkEnd {
+    let tabu = params.tabu;
+    let n = instance.sizes.len();
+    let sampler = MoveSampler::new(instance, &tabu);
+    let mut rng = XorShift64::new(seed);
+
+    let mut current = decreasing_order(instance, &mut rng);
+    let mut cache = PrefixCache::new(instance, tabu.decoder.decoder(), &current);
+    let pack = try_reduce_bins(instance, &cache.packing(instance));
+    let mut best_obj = tabu.objective.score(&pack);
+    shared.offer(&current, &pack, best_obj);
+    if best_obj.0 <= lb {
+        shared.stop.store(true, Ordering::Relaxed);
+        return WalkEnd {
+            iters: 0,
+            timed_out: false,
+        };
+    }
+    let mut best_iter: u32 = 0;
+
+    let mut tabu_q: VecDeque<MoveKey> = VecDeque::new();
+    let mut tabu_set: HashSet<MoveKey> = HashSet::new();
+
+    let mut iters = 0;
+    for it in 1..=tabu.max_iters {
+        if shared.stop.load(Ordering::Relaxed) {
+            break;
+        }
+        if tabu.time_limit.is_some_and(|limit| start.elapsed() >= limit) {
+            return WalkEnd { iters, timed_out: true };
+        }
+        iters = it;
+
+        if it.saturating_sub(best_iter) >= tabu.stagnation_limit {
+            if let Some(elite) = shared.pick(&mut rng) {
+                current = elite;
+            }
+            for _ in 0..params.perturbation {
+                let (i, j) = (rng.gen_range_usize(n), rng.gen_range_usize(n));
+                current.swap(i, j);
+            }
+            cache.rebuild_from(instance, &current, 0);
+            tabu_q.clear();
+            tabu_set.clear();
+            best_iter = it;
+        }
+
+        let Some(chosen) = sampler.best_move(&current, &cache, &tabu_set, best_obj, &mut rng, &mut NoopObserver) else {
+            continue;
+        };
+        current = chosen.order;
+        cache.rebuild_from(instance, &current, chosen.first_changed);
+        tabu_push(&mut tabu_q, &mut tabu_set, chosen.mv, tabu.tabu_tenure);
+
+        if chosen.score < best_obj {
+            best_obj = chosen.score;
+            best_iter = it;
+            shared.offer(&current, &chosen.packing, best_obj);
+            if best_obj.0 <= lb {
+                shared.stop.store(true, Ordering::Relaxed);
+                break;
+            }
+        }
+    }
+    WalkEnd {
+        iters,
+        timed_out: false,
+    }
+}
+
+pub fn multi_start_tabu(instance: &Instance, seed: u64, params: MultiStartParams) -> TabuResult {
+    let start = Instant::now();
+    let threads = match params.threads {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        t => t,
+    };
+    let lb = best_lower_bound(instance);
+    let shared = Shared {
+        elite: Mutex::new(Vec::new()),
+        capacity: params.elite_size.max(1),
+        stop: AtomicBool::new(false),
+    };
+
+    // Every walk gets its own stream, seeded from the call's seed.
+    let mut streams = XorShift64::new(seed);
+    let seeds: Vec<u64> = (0..threads).map(|_| streams.next_u64()).collect();
+    let ends: Vec<WalkEnd> = std::thread::scope(|scope| {
+        let shared = &shared;
+        let walks: Vec<_> = seeds
+            .iter()
+            .map(|&s| scope.spawn(move || walk(instance, &params, lb, s, start, shared)))
+            .collect();
+        walks
+            .into_iter()
+            .map(|w| w.join().unwrap_or_else(|e| std::panic::resume_unwind(e)))
+            .collect()
+    });
+
+    let best = shared.elite.into_inner().unwrap_or_else(|e| e.into_inner()).swap_remove(0);
+    let iters = ends.iter().fold(0u32, |acc, e| acc.saturating_add(e.iters));
+    let stop_reason = if best.score.0 <= lb {
+        StopReason::LowerBound
+    } else if ends.iter().any(|e| e.timed_out) {
+        StopReason::TimeLimit
+    } else {
+        StopReason::MaxIters
+    };
+    let elapsed = start.elapsed();
+    TabuResult {
+        best_unused: packing_objective(&best.packing).1,
+        best_bins: best.score.0,
+        best_order: best.order,
+        best_packing: best.packing,
+        elapsed,
+        iters,
+        iters_per_sec: iters_per_sec(iters, elapsed),
+        stop_reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::ani_instance;
+    use crate::instances::example_instance_tp2;
+    use crate::packing::validate_packing;
+
+    #[test]
+    fn cooperating_walks_return_one_valid_result() {
+        let tp2 = example_instance_tp2();
+        let res = MultiStartParams {
+            threads: 3,
+            ..MultiStartParams::default()
+        }
+        .solve(&tp2, 1);
+        validate_packing(&tp2, &res.best_packing).unwrap();
+        assert_eq!((res.best_bins, res.stop_reason), (4, StopReason::LowerBound));
+
+        // ANI: the bound says 20 bins, but no packing in fewer than 21 exists, so every walk
+        // runs to its iteration limit.
+        let inst = ani_instance("multi-ani", 20, 1000, 4).unwrap();
+        assert_eq!((best_lower_bound(&inst), inst.opt_bins), (20, Some(21)));
+        let params = MultiStartParams {
+            tabu: TabuParams {
+                max_iters: 150,
+                stagnation_limit: 20,
+                ..TabuParams::default()
+            },
+            threads: 4,
+            elite_size: 3,
+            perturbation: 3,
+        };
+        let res = params.solve(&inst, 0);
+        validate_packing(&inst, &res.best_packing).unwrap();
+        assert_eq!(res.best_bins, res.best_packing.n_bins());
+        assert!(res.best_bins >= 21);
+        assert_eq!(res.stop_reason, StopReason::MaxIters);
+        assert_eq!(res.iters, 4 * 150);
+    }
+}
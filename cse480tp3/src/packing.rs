@@ -1,3 +1,8 @@
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::Path;
+
+use crate::exact::{bin_completion, ExactLimits};
 use crate::instances::Instance;
 
 #[derive(Clone, Debug)]
@@ -15,41 +20,232 @@ impl Packing {
 
 pub fn lower_bound_bins(instance: &Instance) -> usize {
     let sum: u32 = instance.sizes.iter().copied().sum();
-    ((sum + instance.capacity - 1) / instance.capacity) as usize
+    sum.div_ceil(instance.capacity) as usize
 }
 
-pub fn best_fit_pack(instance: &Instance, order: &[usize]) -> Packing {
-    let mut bins: Vec<Vec<usize>> = Vec::new();
-    let mut loads: Vec<u32> = Vec::new();
+// Open bins keyed by (residual capacity, bin index). The first key with residual >= size is
+// the tightest bin, and the lowest-indexed one among equally tight bins, which is exactly
+// the choice of a left-to-right scan keeping strict improvements.
+struct BestFitBins {
+    capacity: u32,
+    free: BTreeSet<(u32, usize)>,
+}
 
-    for &item_id in order {
-        let size = instance.sizes[item_id];
-        let mut best_bin_idx: Option<usize> = None;
-        let mut best_remaining: Option<u32> = None;
+impl BestFitBins {
+    fn from_loads(capacity: u32, loads: &[u32]) -> Self {
+        let free = loads
+            .iter()
+            .enumerate()
+            .map(|(idx, &load)| (capacity - load, idx))
+            .collect();
+        Self { capacity, free }
+    }
 
-        for (idx, &load) in loads.iter().enumerate() {
-            let remaining = instance.capacity - load;
-            if size <= remaining {
-                let after = remaining - size;
-                if best_remaining.is_none() || after < best_remaining.unwrap() {
-                    best_remaining = Some(after);
-                    best_bin_idx = Some(idx);
-                }
+    fn place(&mut self, size: u32, loads: &mut Vec<u32>) -> usize {
+        match self.free.range((size, 0)..).next().copied() {
+            Some((remaining, idx)) => {
+                self.free.remove(&(remaining, idx));
+                self.free.insert((remaining - size, idx));
+                loads[idx] += size;
+                idx
             }
-        }
-
-        match best_bin_idx {
             None => {
-                bins.push(vec![item_id]);
+                let idx = loads.len();
+                self.free.insert((self.capacity - size, idx));
                 loads.push(size);
+                idx
             }
-            Some(idx) => {
-                bins[idx].push(item_id);
-                loads[idx] += size;
+        }
+    }
+}
+
+// Turns a permutation into a packing by placing items one at a time. Implementations only
+// look at the current bin loads, so decoding can resume from any partial packing.
+pub trait Decoder {
+    fn name(&self) -> &'static str;
+
+    // Places `items` one by one on top of an existing partial packing described by `loads`,
+    // appending the chosen bin of every item to `assign`.
+    fn extend(&self, instance: &Instance, items: &[usize], loads: &mut Vec<u32>, assign: &mut Vec<usize>);
+
+    fn pack(&self, instance: &Instance, order: &[usize]) -> Packing {
+        let mut loads: Vec<u32> = Vec::new();
+        let mut assign: Vec<usize> = Vec::with_capacity(order.len());
+        self.extend(instance, order, &mut loads, &mut assign);
+        packing_from_assignment(instance, order, &assign, loads)
+    }
+}
+
+fn place_in(idx: Option<usize>, size: u32, loads: &mut Vec<u32>) -> usize {
+    match idx {
+        Some(idx) => {
+            loads[idx] += size;
+            idx
+        }
+        None => {
+            loads.push(size);
+            loads.len() - 1
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstFit;
+
+impl Decoder for FirstFit {
+    fn name(&self) -> &'static str {
+        "first-fit"
+    }
+
+    fn extend(&self, instance: &Instance, items: &[usize], loads: &mut Vec<u32>, assign: &mut Vec<usize>) {
+        for &item_id in items {
+            let size = instance.sizes[item_id];
+            let idx = loads.iter().position(|&load| size <= instance.capacity - load);
+            assign.push(place_in(idx, size, loads));
+        }
+    }
+}
+
+// Only the most recently opened bin is ever considered.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NextFit;
+
+impl Decoder for NextFit {
+    fn name(&self) -> &'static str {
+        "next-fit"
+    }
+
+    fn extend(&self, instance: &Instance, items: &[usize], loads: &mut Vec<u32>, assign: &mut Vec<usize>) {
+        for &item_id in items {
+            let size = instance.sizes[item_id];
+            let idx = loads
+                .len()
+                .checked_sub(1)
+                .filter(|&last| size <= instance.capacity - loads[last]);
+            assign.push(place_in(idx, size, loads));
+        }
+    }
+}
+
+// Emptiest feasible bin, lowest index on ties.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WorstFit;
+
+impl Decoder for WorstFit {
+    fn name(&self) -> &'static str {
+        "worst-fit"
+    }
+
+    fn extend(&self, instance: &Instance, items: &[usize], loads: &mut Vec<u32>, assign: &mut Vec<usize>) {
+        for &item_id in items {
+            let size = instance.sizes[item_id];
+            let mut best: Option<(u32, usize)> = None;
+            for (idx, &load) in loads.iter().enumerate() {
+                let remaining = instance.capacity - load;
+                if size <= remaining && best.is_none_or(|(r, _)| remaining > r) {
+                    best = Some((remaining, idx));
+                }
             }
+            assign.push(place_in(best.map(|(_, idx)| idx), size, loads));
         }
     }
+}
+
+// Second-emptiest feasible bin, falling back to the emptiest when only one bin fits.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AlmostWorstFit;
 
+impl Decoder for AlmostWorstFit {
+    fn name(&self) -> &'static str {
+        "almost-worst-fit"
+    }
+
+    fn extend(&self, instance: &Instance, items: &[usize], loads: &mut Vec<u32>, assign: &mut Vec<usize>) {
+        for &item_id in items {
+            let size = instance.sizes[item_id];
+            let mut first: Option<(u32, usize)> = None;
+            let mut second: Option<(u32, usize)> = None;
+            for (idx, &load) in loads.iter().enumerate() {
+                let remaining = instance.capacity - load;
+                if size > remaining {
+                    continue;
+                }
+                if first.is_none_or(|(r, _)| remaining > r) {
+                    second = first;
+                    first = Some((remaining, idx));
+                } else if second.is_none_or(|(r, _)| remaining > r) {
+                    second = Some((remaining, idx));
+                }
+            }
+            assign.push(place_in(second.or(first).map(|(_, idx)| idx), size, loads));
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BestFit;
+
+impl Decoder for BestFit {
+    fn name(&self) -> &'static str {
+        "best-fit"
+    }
+
+    fn extend(&self, instance: &Instance, items: &[usize], loads: &mut Vec<u32>, assign: &mut Vec<usize>) {
+        let mut open = BestFitBins::from_loads(instance.capacity, loads);
+        for &item_id in items {
+            assign.push(open.place(instance.sizes[item_id], loads));
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DecoderKind {
+    FirstFit,
+    NextFit,
+    WorstFit,
+    AlmostWorstFit,
+    #[default]
+    BestFit,
+}
+
+impl DecoderKind {
+    pub const ALL: [DecoderKind; 5] = [
+        DecoderKind::FirstFit,
+        DecoderKind::NextFit,
+        DecoderKind::WorstFit,
+        DecoderKind::AlmostWorstFit,
+        DecoderKind::BestFit,
+    ];
+
+    pub fn decoder(self) -> &'static dyn Decoder {
+        match self {
+            DecoderKind::FirstFit => &FirstFit,
+            DecoderKind::NextFit => &NextFit,
+            DecoderKind::WorstFit => &WorstFit,
+            DecoderKind::AlmostWorstFit => &AlmostWorstFit,
+            DecoderKind::BestFit => &BestFit,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        self.decoder().name()
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|k| {
+            let full = k.name();
+            let short: String = full.split('-').filter_map(|w| w.chars().next()).collect();
+            name == full || name == short
+        })
+    }
+}
+
+fn packing_from_assignment(instance: &Instance, order: &[usize], assign: &[usize], loads: Vec<u32>) -> Packing {
+    let mut bins: Vec<Vec<usize>> = vec![Vec::new(); loads.len()];
+    for (&item_id, &b) in order.iter().zip(assign.iter()) {
+        bins[b].push(item_id);
+    }
     Packing {
         capacity: instance.capacity,
         bins,
@@ -57,6 +253,84 @@ pub fn best_fit_pack(instance: &Instance, order: &[usize]) -> Packing {
     }
 }
 
+pub fn best_fit_pack(instance: &Instance, order: &[usize]) -> Packing {
+    BestFit.pack(instance, order)
+}
+
+// Decoder state cached for every prefix of a fixed order, so that a neighbour which only
+// differs from that order at positions >= p can be decoded from position p.
+#[derive(Clone)]
+pub struct PrefixCache<'d> {
+    decoder: &'d dyn Decoder,
+    order: Vec<usize>,
+    assign: Vec<usize>,
+    // Loads after the first k items live in snaps[snap_start[k]..snap_start[k + 1]].
+    snap_start: Vec<usize>,
+    snaps: Vec<u32>,
+}
+
+impl<'d> PrefixCache<'d> {
+    pub fn new(instance: &Instance, decoder: &'d dyn Decoder, order: &[usize]) -> Self {
+        let mut cache = Self {
+            decoder,
+            order: Vec::with_capacity(order.len()),
+            assign: Vec::with_capacity(order.len()),
+            snap_start: vec![0, 0],
+            snaps: Vec::new(),
+        };
+        cache.rebuild_from(instance, order, 0);
+        cache
+    }
+
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    fn loads_after(&self, k: usize) -> &[u32] {
+        &self.snaps[self.snap_start[k]..self.snap_start[k + 1]]
+    }
+
+    // Replaces the cached order by `order`, which must agree with it before `first_changed`.
+    pub fn rebuild_from(&mut self, instance: &Instance, order: &[usize], first_changed: usize) {
+        let p = first_changed.min(self.order.len());
+        debug_assert_eq!(self.order[..p], order[..p]);
+
+        self.order.truncate(p);
+        self.order.extend_from_slice(&order[p..]);
+        self.assign.truncate(p);
+        self.snaps.truncate(self.snap_start[p + 1]);
+        self.snap_start.truncate(p + 2);
+
+        let mut loads = self.loads_after(p).to_vec();
+        let mut suffix_loads = loads.clone();
+        self.decoder.extend(instance, &order[p..], &mut suffix_loads, &mut self.assign);
+
+        // Replay the suffix assignment to record the loads after every prefix.
+        for (&item_id, &b) in order[p..].iter().zip(self.assign[p..].iter()) {
+            place_in((b < loads.len()).then_some(b), instance.sizes[item_id], &mut loads);
+            self.snaps.extend_from_slice(&loads);
+            self.snap_start.push(self.snaps.len());
+        }
+    }
+
+    pub fn packing(&self, instance: &Instance) -> Packing {
+        let loads = self.loads_after(self.order.len()).to_vec();
+        packing_from_assignment(instance, &self.order, &self.assign, loads)
+    }
+
+    // Decodes `candidate`, which must agree with the cached order before `first_changed`.
+    pub fn evaluate(&self, instance: &Instance, candidate: &[usize], first_changed: usize) -> Packing {
+        let p = first_changed.min(self.order.len());
+        debug_assert_eq!(self.order[..p], candidate[..p]);
+
+        let mut loads = self.loads_after(p).to_vec();
+        let mut assign = Vec::with_capacity(candidate.len());
+        assign.extend_from_slice(&self.assign[..p]);
+        self.decoder.extend(instance, &candidate[p..], &mut loads, &mut assign);
+        packing_from_assignment(instance, candidate, &assign, loads)
+    }
+}
+
 pub fn packing_objective(packing: &Packing) -> (usize, u32) {
     let unused: u32 = packing
         .bin_loads
@@ -66,46 +340,254 @@ pub fn packing_objective(packing: &Packing) -> (usize, u32) {
     (packing.n_bins(), unused)
 }
 
+// Search objectives. All of them rank by bin count first and only differ in how packings
+// with the same number of bins are told apart; smaller scores are better.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Objective {
+    // Total unused capacity. Constant for a given bin count, kept to reproduce old runs.
+    BinsUnused,
+    // Falkenauer's fitness (1/N) * sum((load/C)^k): rewards a few very full bins over
+    // evenly half-full ones.
+    Falkenauer { k: u32 },
+    // Items in the least-filled bin (then its fill ratio): fewer means closer to emptying it.
+    MinBinItems,
+    // Sum of squared loads, a scale-free cousin of Falkenauer's k = 2.
+    SquaredLoads,
+}
+
+impl Default for Objective {
+    fn default() -> Self {
+        Objective::Falkenauer { k: 2 }
+    }
+}
+
+impl Objective {
+    // Empty bins are ignored, so a packing can be scored while a move has emptied a bin
+    // that has not been compacted away yet.
+    pub fn score(&self, packing: &Packing) -> (usize, f64) {
+        let n_bins = packing.bin_loads.iter().filter(|&&load| load > 0).count();
+        let cap = packing.capacity as f64;
+        let secondary = match *self {
+            Objective::BinsUnused => {
+                let used: u64 = packing.bin_loads.iter().map(|&load| load as u64).sum();
+                (n_bins as u64 * packing.capacity as u64 - used) as f64
+            }
+            Objective::Falkenauer { k } => {
+                if n_bins == 0 {
+                    0.0
+                } else {
+                    let sum: f64 = packing
+                        .bin_loads
+                        .iter()
+                        .map(|&load| (load as f64 / cap).powi(k as i32))
+                        .sum();
+                    -sum / (n_bins as f64)
+                }
+            }
+            Objective::MinBinItems => packing
+                .bins
+                .iter()
+                .zip(packing.bin_loads.iter())
+                .filter(|(_, &load)| load > 0)
+                .min_by_key(|(_, &load)| load)
+                .map(|(items, &load)| items.len() as f64 + load as f64 / cap)
+                .unwrap_or(0.0),
+            Objective::SquaredLoads => {
+                -packing
+                    .bin_loads
+                    .iter()
+                    .map(|&load| (load as f64) * (load as f64))
+                    .sum::<f64>()
+            }
+        };
+        (n_bins, secondary)
+    }
+
+    // Single number for acceptance tests such as Metropolis: the bin count plus the
+    // tie-breaker squashed into [0, 1), so one bin always outweighs any tie-breaker change.
+    pub fn energy(&self, packing: &Packing) -> f64 {
+        let (n_bins, secondary) = self.score(packing);
+        let cap = packing.capacity as f64;
+        let squashed = match *self {
+            Objective::BinsUnused => 0.0,
+            Objective::Falkenauer { .. } => 1.0 + secondary,
+            Objective::MinBinItems => {
+                let n_items: usize = packing.bins.iter().map(|b| b.len()).sum();
+                secondary / (n_items as f64 + 1.0)
+            }
+            Objective::SquaredLoads => {
+                if n_bins == 0 {
+                    0.0
+                } else {
+                    1.0 + secondary / (n_bins as f64 * cap * cap)
+                }
+            }
+        };
+        n_bins as f64 + squashed.clamp(0.0, 0.999_999)
+    }
+
+    pub fn name(&self) -> String {
+        match *self {
+            Objective::BinsUnused => "bins-unused".to_string(),
+            Objective::Falkenauer { k } => format!("falkenauer:{k}"),
+            Objective::MinBinItems => "min-bin-items".to_string(),
+            Objective::SquaredLoads => "squared-loads".to_string(),
+        }
+    }
+
+    // Accepts the names produced by `name`; `falkenauer` alone means k = 2.
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if let Some(rest) = name.strip_prefix("falkenauer") {
+            if rest.is_empty() {
+                return Some(Objective::Falkenauer { k: 2 });
+            }
+            let k = rest.strip_prefix(':')?.parse::<u32>().ok()?;
+            return (k >= 1).then_some(Objective::Falkenauer { k });
+        }
+        match name.as_str() {
+            "bins-unused" | "bins" => Some(Objective::BinsUnused),
+            "min-bin-items" => Some(Objective::MinBinItems),
+            "squared-loads" => Some(Objective::SquaredLoads),
+            _ => None,
+        }
+    }
+}
+
 pub fn validate_packing(instance: &Instance, packing: &Packing) -> Result<(), String> {
+    match packing_violations(instance, packing).into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+// Every way in which `packing` fails to be a feasible packing of `instance`, in file order.
+pub fn packing_violations(instance: &Instance, packing: &Packing) -> Vec<String> {
     let n = instance.sizes.len();
     let mut seen = vec![false; n];
+    let mut out = Vec::new();
 
     if packing.capacity != instance.capacity {
-        return Err("Capacity mismatch".to_string());
+        out.push("Capacity mismatch".to_string());
     }
 
     if packing.bins.len() != packing.bin_loads.len() {
-        return Err("bins/bin_loads length mismatch".to_string());
+        out.push("bins/bin_loads length mismatch".to_string());
     }
 
-    for (bin_items, &load) in packing.bins.iter().zip(packing.bin_loads.iter()) {
-        let computed: u32 = bin_items.iter().map(|&i| instance.sizes[i]).sum();
-        if computed != load {
-            return Err(format!("Bin load mismatch: expected {computed}, got {load}"));
+    // Bins and items are numbered from 1, as in solution files.
+    for (b, (bin_items, &load)) in packing.bins.iter().zip(packing.bin_loads.iter()).enumerate() {
+        // Summed in u64: a hand-edited file may list the same large item many times.
+        let computed: u64 = bin_items.iter().filter_map(|&i| instance.sizes.get(i)).map(|&s| s as u64).sum();
+        if computed != load as u64 {
+            out.push(format!("Bin {}: load mismatch: expected {computed}, got {load}", b + 1));
         }
-        if load > instance.capacity {
-            return Err(format!(
-                "Infeasible bin: load {load} > capacity {}",
+        if computed > instance.capacity as u64 {
+            out.push(format!(
+                "Bin {}: infeasible: load {computed} > capacity {}",
+                b + 1,
                 instance.capacity
             ));
         }
         for &i in bin_items {
             if i >= n {
-                return Err(format!("Invalid item id: {i}"));
-            }
-            if seen[i] {
-                return Err(format!("Item appears more than once: {i}"));
+                out.push(format!("Bin {}: invalid item id: {}", b + 1, i + 1));
+            } else if seen[i] {
+                out.push(format!("Bin {}: item appears more than once: {}", b + 1, i + 1));
+            } else {
+                seen[i] = true;
             }
-            seen[i] = true;
         }
     }
 
-    if let Some((idx, _)) = seen.iter().enumerate().find(|(_, ok)| !**ok) {
-        return Err(format!("Missing item in packing: {idx}"));
+    for (idx, _) in seen.iter().enumerate().filter(|(_, ok)| !**ok) {
+        out.push(format!("Missing item in packing: {}", idx + 1));
+    }
+    out
+}
+
+// Solution file, one bin per line with its load and 1-based item ids (input order):
+//   # cse480tp3 solution
+//   instance <name>
+//   capacity <C>
+//   bins <k>
+//   bin <load>: <id> <id> ...
+pub fn write_solution<W: Write>(instance: &Instance, packing: &Packing, out: &mut W) -> std::io::Result<()> {
+    writeln!(out, "# cse480tp3 solution")?;
+    writeln!(out, "instance {}", instance.name.trim())?;
+    writeln!(out, "capacity {}", packing.capacity)?;
+    writeln!(out, "bins {}", packing.n_bins())?;
+    for (bin, load) in packing.bins.iter().zip(packing.bin_loads.iter()) {
+        let ids = bin.iter().map(|&i| (i + 1).to_string()).collect::<Vec<_>>().join(" ");
+        writeln!(out, "bin {load}: {ids}")?;
     }
     Ok(())
 }
 
+#[derive(Clone, Debug)]
+pub struct Solution {
+    pub instance_name: String,
+    pub packing: Packing,
+}
+
+// Only the syntax is checked here; `packing_violations` judges the packing itself.
+pub fn parse_solution(content: &str) -> Result<Solution, String> {
+    let mut name: Option<String> = None;
+    let mut capacity: Option<u32> = None;
+    let mut declared: Option<usize> = None;
+    let mut bins: Vec<Vec<usize>> = Vec::new();
+    let mut bin_loads: Vec<u32> = Vec::new();
+
+    for (lineno, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let err = |what: &str| format!("line {}: {what}: `{line}`", lineno + 1);
+        let (key, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim();
+        match key {
+            "instance" => name = Some(rest.to_string()),
+            "capacity" => capacity = Some(rest.parse().map_err(|_| err("invalid capacity"))?),
+            "bins" => declared = Some(rest.parse().map_err(|_| err("invalid bin count"))?),
+            "bin" => {
+                let (load, ids) = rest.split_once(':').ok_or_else(|| err("expected `bin <load>: <ids>`"))?;
+                bin_loads.push(load.trim().parse().map_err(|_| err("invalid bin load"))?);
+                let mut bin = Vec::new();
+                for tok in ids.split_whitespace() {
+                    match tok.parse::<usize>() {
+                        Ok(id) if id >= 1 => bin.push(id - 1),
+                        _ => return Err(err(&format!("invalid item id `{tok}` (ids start at 1)"))),
+                    }
+                }
+                bins.push(bin);
+            }
+            _ => return Err(err("unknown line")),
+        }
+    }
+
+    let capacity = capacity.ok_or("missing `capacity` line")?;
+    if let Some(k) = declared {
+        if k != bins.len() {
+            return Err(format!("header declares {k} bins, file lists {}", bins.len()));
+        }
+    }
+    Ok(Solution {
+        instance_name: name.ok_or("missing `instance` line")?,
+        packing: Packing {
+            capacity,
+            bins,
+            bin_loads,
+        },
+    })
+}
+
+pub fn read_solution(path: impl AsRef<Path>) -> Result<Solution, String> {
+    let path = path.as_ref();
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    parse_solution(&content).map_err(|e| format!("{}: {e}", path.display()))
+}
+
 pub fn try_reduce_bins(instance: &Instance, packing: &Packing) -> Packing {
     let mut bins = packing.bins.clone();
     let mut loads = packing.bin_loads.clone();
@@ -117,10 +599,28 @@ pub fn try_reduce_bins(instance: &Instance, packing: &Packing) -> Packing {
         let mut indices: Vec<usize> = (0..bins.len()).collect();
         indices.sort_by_key(|&i| loads[i]);
 
+        // Two largest residual capacities: a bin whose largest item fits in neither of the
+        // other bins' residuals cannot be emptied, so its attempt can be skipped outright.
+        let mut top: [(u32, usize); 2] = [(0, usize::MAX); 2];
+        for (idx, &load) in loads.iter().enumerate() {
+            let remaining = instance.capacity - load;
+            if remaining > top[0].0 {
+                top[1] = top[0];
+                top[0] = (remaining, idx);
+            } else if remaining > top[1].0 {
+                top[1] = (remaining, idx);
+            }
+        }
+
         'outer: for source_idx in indices {
             if bins[source_idx].is_empty() {
                 continue;
             }
+            let room = if top[0].1 == source_idx { top[1].0 } else { top[0].0 };
+            let largest = bins[source_idx].iter().map(|&i| instance.sizes[i]).max().unwrap_or(0);
+            if largest > room {
+                continue;
+            }
             let mut placements: Vec<(usize, usize)> = Vec::new();
 
             let mut items_to_move = bins[source_idx].clone();
@@ -132,11 +632,11 @@ pub fn try_reduce_bins(instance: &Instance, packing: &Packing) -> Packing {
                 let mut target_idx: Option<usize> = None;
                 let mut best_after: Option<u32> = None;
 
-                for idx in 0..bins.len() {
+                for (idx, &load) in loads.iter().enumerate() {
                     if idx == source_idx {
                         continue;
                     }
-                    let remaining = instance.capacity - loads[idx];
+                    let remaining = instance.capacity - load;
                     if size <= remaining {
                         let after = remaining - size;
                         if best_after.is_none() || after < best_after.unwrap() {
@@ -170,7 +670,7 @@ pub fn try_reduce_bins(instance: &Instance, packing: &Packing) -> Packing {
 
             let mut new_bins = Vec::with_capacity(bins.len());
             let mut new_loads = Vec::with_capacity(loads.len());
-            for (b, l) in bins.into_iter().zip(loads.into_iter()) {
+            for (b, l) in bins.into_iter().zip(loads) {
                 if !b.is_empty() {
                     new_bins.push(b);
                     new_loads.push(l);
@@ -235,17 +735,54 @@ pub fn exact_min_bins(instance: &Instance) -> Result<usize, String> {
     Ok(best)
 }
 
+// Optimum by bin completion under the default node/time budget; None if it is not proven.
 pub fn exact_bins_if_small(instance: &Instance, max_items: usize) -> Option<usize> {
     if instance.sizes.len() > max_items {
         return None;
     }
-    exact_min_bins(instance).ok()
+    let res = bin_completion(instance, ExactLimits::default()).ok()?;
+    res.proven.then(|| res.best.n_bins())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::instances::example_instance_tp2;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::rng::XorShift64;
+
+    #[test]
+    fn solution_file_round_trips_and_reports_every_violation() {
+        let inst = example_instance_tp2();
+        let order: Vec<usize> = (0..inst.sizes.len()).collect();
+        let packing = best_fit_pack(&inst, &order);
+        let mut buf = Vec::new();
+        write_solution(&inst, &packing, &mut buf).unwrap();
+        let sol = parse_solution(std::str::from_utf8(&buf).unwrap()).unwrap();
+        assert_eq!(sol.instance_name, inst.name);
+        assert_eq!(sol.packing.bins, packing.bins);
+        assert!(packing_violations(&inst, &sol.packing).is_empty());
+
+        // Item 1 twice, item 7 missing, and an overfull bin with a wrong load.
+        let bad = "instance x\ncapacity 60\nbins 3\nbin 39: 1 2\nbin 100: 3 4 5 1\nbin 27: 6\n";
+        let sol = parse_solution(bad).unwrap();
+        let violations = packing_violations(&inst, &sol.packing);
+        assert_eq!(violations.len(), 4, "{violations:?}");
+        assert!(violations.iter().any(|v| v == "Bin 2: item appears more than once: 1"));
+        assert!(violations.iter().any(|v| v == "Missing item in packing: 7"));
+        assert!(parse_solution("capacity 60\nbin 10: 0\n").is_err());
+
+        let big = Instance {
+            name: "big".to_string(),
+            capacity: 3_000_000_000,
+            sizes: vec![3_000_000_000],
+            opt_bins: None,
+            best_known: None,
+            known_opt: None,
+        };
+        let sol = parse_solution("instance big\ncapacity 3000000000\nbin 4: 1 1 1\n").unwrap();
+        let violations = packing_violations(&big, &sol.packing);
+        assert!(violations.iter().any(|v| v == "Bin 1: infeasible: load 9000000000 > capacity 3000000000"));
+    }
 
     #[test]
     fn tp2_example_optimum_is_4() {
@@ -253,4 +790,144 @@ mod tests {
         let opt = exact_min_bins(&inst).unwrap();
         assert_eq!(opt, 4);
     }
+
+    // Straightforward O(n * bins) best fit, kept as the reference for tie-breaking.
+    fn best_fit_pack_scan(instance: &Instance, order: &[usize]) -> Packing {
+        let mut bins: Vec<Vec<usize>> = Vec::new();
+        let mut loads: Vec<u32> = Vec::new();
+        for &item_id in order {
+            let size = instance.sizes[item_id];
+            let mut best: Option<(u32, usize)> = None;
+            for (idx, &load) in loads.iter().enumerate() {
+                let remaining = instance.capacity - load;
+                if size <= remaining && best.is_none_or(|(after, _)| remaining - size < after) {
+                    best = Some((remaining - size, idx));
+                }
+            }
+            match best {
+                None => {
+                    bins.push(vec![item_id]);
+                    loads.push(size);
+                }
+                Some((_, idx)) => {
+                    bins[idx].push(item_id);
+                    loads[idx] += size;
+                }
+            }
+        }
+        Packing {
+            capacity: instance.capacity,
+            bins,
+            bin_loads: loads,
+        }
+    }
+
+    #[test]
+    fn tree_best_fit_matches_linear_scan() {
+        let mut rng = XorShift64::new(3);
+        for seed in 0..20 {
+            // Small size range to force many ties between equally tight bins.
+            let inst = synthetic_instance("best-fit", 150, 100, 10, 40, seed);
+            let mut order: Vec<usize> = (0..inst.sizes.len()).collect();
+            rng.shuffle(&mut order);
+            let tree = best_fit_pack(&inst, &order);
+            let scan = best_fit_pack_scan(&inst, &order);
+            assert_eq!(tree.bins, scan.bins);
+            assert_eq!(tree.bin_loads, scan.bin_loads);
+        }
+    }
+
+    #[test]
+    fn prefix_cache_matches_full_best_fit_decode() {
+        let inst = synthetic_instance("prefix-cache", 80, 150, 10, 100, 7);
+        let mut rng = XorShift64::new(11);
+        let mut order: Vec<usize> = (0..inst.sizes.len()).collect();
+        rng.shuffle(&mut order);
+        let mut cache = PrefixCache::new(&inst, &BestFit, &order);
+
+        for step in 0..500 {
+            let i = rng.gen_range_usize(order.len());
+            let j = rng.gen_range_usize(order.len());
+            let mut candidate = order.clone();
+            if step % 2 == 0 {
+                candidate.swap(i, j);
+            } else {
+                let item = candidate.remove(i);
+                candidate.insert(j, item);
+            }
+
+            let full = best_fit_pack(&inst, &candidate);
+            let delta = cache.evaluate(&inst, &candidate, i.min(j));
+            assert_eq!(delta.bins, full.bins);
+            assert_eq!(delta.bin_loads, full.bin_loads);
+
+            if step % 3 == 0 {
+                order = candidate;
+                cache.rebuild_from(&inst, &order, i.min(j));
+                let cached = cache.packing(&inst);
+                assert_eq!(cached.bins, best_fit_pack(&inst, &order).bins);
+            }
+        }
+    }
+
+    #[test]
+    fn every_decoder_resumes_from_a_prefix_and_stays_feasible() {
+        let inst = synthetic_instance("decoders", 60, 150, 10, 100, 5);
+        let mut rng = XorShift64::new(9);
+        let mut order: Vec<usize> = (0..inst.sizes.len()).collect();
+        rng.shuffle(&mut order);
+
+        for kind in DecoderKind::ALL {
+            let decoder = kind.decoder();
+            let full = decoder.pack(&inst, &order);
+            validate_packing(&inst, &full).unwrap();
+            assert!(full.n_bins() >= lower_bound_bins(&inst));
+
+            let mut candidate = order.clone();
+            candidate.swap(20, 45);
+            let cache = PrefixCache::new(&inst, decoder, &order);
+            let delta = cache.evaluate(&inst, &candidate, 20);
+            assert_eq!(delta.bins, decoder.pack(&inst, &candidate).bins, "{}", kind.name());
+        }
+    }
+
+    #[test]
+    fn load_aware_objectives_separate_equal_bin_counts() {
+        // Same items, same bin count: [60,30] [50,40] versus the fuller [60,40] [50,30].
+        let inst = Instance {
+            name: "objective".to_string(),
+            capacity: 100,
+            sizes: vec![60, 50, 40, 30],
+            opt_bins: None,
+            best_known: None,
+            known_opt: None,
+        };
+        let even = Packing {
+            capacity: 100,
+            bins: vec![vec![0, 3], vec![1, 2]],
+            bin_loads: vec![90, 90],
+        };
+        let uneven = Packing {
+            capacity: 100,
+            bins: vec![vec![0, 2], vec![1, 3]],
+            bin_loads: vec![100, 80],
+        };
+        validate_packing(&inst, &even).unwrap();
+        validate_packing(&inst, &uneven).unwrap();
+
+        assert_eq!(Objective::BinsUnused.score(&even), Objective::BinsUnused.score(&uneven));
+        for obj in [Objective::Falkenauer { k: 2 }, Objective::SquaredLoads, Objective::MinBinItems] {
+            assert!(obj.score(&uneven) < obj.score(&even), "{}", obj.name());
+            assert_eq!(Objective::parse(&obj.name()), Some(obj));
+        }
+    }
+
+    #[test]
+    fn decoder_names_round_trip() {
+        for kind in DecoderKind::ALL {
+            assert_eq!(DecoderKind::parse(kind.name()), Some(kind));
+        }
+        assert_eq!(DecoderKind::parse("awf"), Some(DecoderKind::AlmostWorstFit));
+        assert_eq!(DecoderKind::parse("nope"), None);
+    }
 }
@@ -0,0 +1,150 @@
+use crate::bounds::mtrp_reduction;
+use crate::instances::Instance;
+use crate::packing::{exact_min_bins, packing_objective, validate_packing, Packing};
+use crate::solver::Solver;
+use crate::tabu::{iters_per_sec, StopReason, TabuResult};
+
+// An instance after MTRP preprocessing: the bins fixed by the reduction, and the remaining
+// items as a smaller instance. `original[k]` is the item of the full instance behind item k
+// of `reduced`.
+#[derive(Clone, Debug)]
+pub struct Reduction {
+    pub reduced: Instance,
+    pub fixed: Packing,
+    pub original: Vec<usize>,
+}
+
+impl Reduction {
+    // Maps a packing of the reduced instance back to the full one, after the fixed bins.
+    pub fn merge(&self, instance: &Instance, packing: &Packing) -> Result<Packing, String> {
+        let mut merged = self.fixed.clone();
+        for (bin, &load) in packing.bins.iter().zip(packing.bin_loads.iter()) {
+            merged.bins.push(bin.iter().map(|&k| self.original[k]).collect());
+            merged.bin_loads.push(load);
+        }
+        validate_packing(instance, &merged)?;
+        Ok(merged)
+    }
+}
+
+// Applies the Martello-Toth reduction until it fixes no more bins.
+pub fn reduce_instance(instance: &Instance) -> Reduction {
+    let mut items: Vec<usize> = (0..instance.sizes.len()).collect();
+    items.sort_by_key(|&i| std::cmp::Reverse(instance.sizes[i]));
+
+    let mut bins: Vec<Vec<usize>> = Vec::new();
+    loop {
+        let (fixed, rest) = mtrp_reduction(instance, &items);
+        items = rest;
+        if fixed.is_empty() {
+            break;
+        }
+        bins.extend(fixed);
+    }
+    items.sort_unstable();
+
+    let bin_loads = bins
+        .iter()
+        .map(|b| b.iter().map(|&i| instance.sizes[i]).sum())
+        .collect();
+    let n_fixed = bins.len();
+    Reduction {
+        reduced: Instance {
+            name: format!("{}-reduced", instance.name),
+            capacity: instance.capacity,
+            sizes: items.iter().map(|&i| instance.sizes[i]).collect(),
+            opt_bins: instance.opt_bins.map(|b| b.saturating_sub(n_fixed)),
+            best_known: instance.best_known.map(|b| b.saturating_sub(n_fixed)),
+            known_opt: instance.known_opt.map(|b| b.saturating_sub(n_fixed)),
+        },
+        fixed: Packing {
+            capacity: instance.capacity,
+            bins,
+            bin_loads,
+        },
+        original: items,
+    }
+}
+
+pub fn exact_min_bins_reduced(instance: &Instance) -> Result<usize, String> {
+    let reduction = reduce_instance(instance);
+    Ok(reduction.fixed.n_bins() + exact_min_bins(&reduction.reduced)?)
+}
+
+// Runs the wrapped solver on the reduced instance and merges its packing back.
+#[derive(Clone, Copy, Debug)]
+pub struct Reduced<S>(pub S);
+
+impl<S: Solver> Solver for Reduced<S> {
+    // Kept apart from plain runs of the same solver in the logs.
+    fn name(&self) -> String {
+        format!("{}+mtrp", self.0.name())
+    }
+
+    fn solve(&self, instance: &Instance, seed: u64) -> TabuResult {
+        let start = std::time::Instant::now();
+        let reduction = reduce_instance(instance);
+        let (partial, iters, stop_reason) = if reduction.reduced.sizes.is_empty() {
+            (
+                Packing {
+                    capacity: instance.capacity,
+                    bins: Vec::new(),
+                    bin_loads: Vec::new(),
+                },
+                0,
+                // The reduction alone packed every item optimally.
+                StopReason::LowerBound,
+            )
+        } else {
+            let res = self.0.solve(&reduction.reduced, seed);
+            (res.best_packing, res.iters, res.stop_reason)
+        };
+
+        let packing = reduction
+            .merge(instance, &partial)
+            .unwrap_or_else(|e| panic!("{}: merged packing is invalid: {e}", instance.name));
+        let elapsed = start.elapsed();
+        let (best_bins, best_unused) = packing_objective(&packing);
+        TabuResult {
+            best_order: packing.bins.concat(),
+            best_packing: packing,
+            best_bins,
+            best_unused,
+            elapsed,
+            iters,
+            iters_per_sec: iters_per_sec(iters, elapsed),
+            stop_reason,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::tabu::TabuParams;
+
+    #[test]
+    fn reduced_search_and_exact_agree_with_the_full_instance() {
+        let tp2 = example_instance_tp2();
+        assert_eq!(exact_min_bins_reduced(&tp2).unwrap(), exact_min_bins(&tp2).unwrap());
+
+        // Large items leave little room, so the reduction fixes a good share of the bins.
+        let inst = synthetic_instance("reduce", 80, 150, 30, 120, 3);
+        let reduction = reduce_instance(&inst);
+        assert!(reduction.fixed.n_bins() > 0);
+        assert_eq!(
+            reduction.fixed.bins.iter().map(|b| b.len()).sum::<usize>() + reduction.reduced.sizes.len(),
+            inst.sizes.len()
+        );
+
+        let params = TabuParams {
+            max_iters: 300,
+            ..TabuParams::default()
+        };
+        assert_eq!(Reduced(params).name(), "tabu+mtrp");
+        let res = Reduced(params).solve(&inst, 0);
+        validate_packing(&inst, &res.best_packing).unwrap();
+        assert_eq!(res.best_bins, res.best_packing.n_bins());
+    }
+}
@@ -0,0 +1,21 @@
+use crate::instances::Instance;
+use crate::tabu::TabuResult;
+
+// Anything that turns an instance and a seed into a packing. Experiment runners only see this
+// trait, so a new algorithm needs an implementation here and a name in the CLI, nothing more.
+pub trait Solver: Send + Sync {
+    // Shown in the run logs; wrappers such as `Reduced` build theirs from the inner solver's.
+    fn name(&self) -> String;
+
+    fn solve(&self, instance: &Instance, seed: u64) -> TabuResult;
+}
+
+impl<S: Solver + ?Sized> Solver for Box<S> {
+    fn name(&self) -> String {
+        (**self).name()
+    }
+
+    fn solve(&self, instance: &Instance, seed: u64) -> TabuResult {
+        (**self).solve(instance, seed)
+    }
+}
@@ -1,11 +1,15 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::hash::Hash;
 use std::io::Write;
 use std::time::{Duration, Instant};
 
+use crate::bounds::best_lower_bound;
 use crate::instances::Instance;
-use crate::packing::{best_fit_pack, lower_bound_bins, packing_objective, try_reduce_bins, Packing};
+use crate::packing::{
+    packing_objective, try_reduce_bins, DecoderKind, Objective, Packing, PrefixCache,
+};
 use crate::rng::XorShift64;
+use crate::solver::Solver;
 
 #[derive(Clone, Copy, Debug)]
 pub struct TabuParams {
@@ -14,6 +18,17 @@ pub struct TabuParams {
     pub tabu_tenure: usize,
     pub stagnation_limit: u32,
     pub time_limit: Option<Duration>,
+    pub decoder: DecoderKind,
+    pub objective: Objective,
+    pub space: SearchSpace,
+}
+
+// What the tabu search walks over: permutations decoded into packings, or packings directly.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SearchSpace {
+    #[default]
+    Permutation,
+    Bins,
 }
 
 impl Default for TabuParams {
@@ -24,8 +39,24 @@ impl Default for TabuParams {
             tabu_tenure: 25,
             stagnation_limit: 600,
             time_limit: None,
+            decoder: DecoderKind::BestFit,
+            objective: Objective::default(),
+            space: SearchSpace::Permutation,
+        }
+    }
+}
+
+impl Solver for TabuParams {
+    fn name(&self) -> String {
+        match self.space {
+            SearchSpace::Permutation => "tabu".to_string(),
+            SearchSpace::Bins => "bin-tabu".to_string(),
         }
     }
+
+    fn solve(&self, instance: &Instance, seed: u64) -> TabuResult {
+        tabu_search(instance, seed, *self)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -36,20 +67,316 @@ pub struct TabuResult {
     pub best_unused: u32,
     pub elapsed: Duration,
     pub iters: u32,
+    pub iters_per_sec: f64,
+    pub stop_reason: StopReason,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
-enum MoveKey {
+pub enum MoveKey {
     Swap { a: usize, b: usize },
     Insert { item: usize, pos: usize },
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StopReason {
+    MaxIters,
+    TimeLimit,
+    LowerBound,
+}
+
+impl StopReason {
+    pub fn name(self) -> &'static str {
+        match self {
+            StopReason::MaxIters => "max-iters",
+            StopReason::TimeLimit => "time-limit",
+            StopReason::LowerBound => "lower-bound",
+        }
+    }
+}
+
+// Everything the permutation tabu search reports while it runs. Scores are the
+// `Objective::score` pairs (bins, tie-breaker).
+#[derive(Debug)]
+pub enum SearchEvent<'a> {
+    Init {
+        instance: &'a Instance,
+        seed: u64,
+        params: &'a TabuParams,
+        lower_bound: usize,
+        order: &'a [usize],
+        packing: &'a Packing,
+        score: (usize, f64),
+    },
+    IterationStart {
+        it: u32,
+        current: (usize, f64),
+        best: (usize, f64),
+        tabu_len: usize,
+    },
+    // `from`/`to` are the positions the move was sampled with, `items` the items found there.
+    CandidateEvaluated {
+        sample: u32,
+        from: usize,
+        to: usize,
+        items: (usize, usize),
+        mv: MoveKey,
+        score: (usize, f64),
+        is_tabu: bool,
+        aspiration: bool,
+        allowed: bool,
+    },
+    NoAdmissibleCandidate {
+        it: u32,
+    },
+    MoveChosen {
+        it: u32,
+        mv: MoveKey,
+        score: (usize, f64),
+        packing: &'a Packing,
+    },
+    NewBest {
+        it: u32,
+        score: (usize, f64),
+    },
+    Diversification {
+        it: u32,
+    },
+    Stop {
+        it: u32,
+        reason: StopReason,
+        best: (usize, f64),
+        result: &'a TabuResult,
+    },
+}
+
+pub trait SearchObserver {
+    fn on_event(&mut self, event: &SearchEvent<'_>);
+}
+
+pub struct NoopObserver;
+
+impl SearchObserver for NoopObserver {
+    fn on_event(&mut self, _event: &SearchEvent<'_>) {}
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct TraceConfig {
     pub show_candidates: bool,
     pub show_packings: bool,
 }
 
+// Writes the human-readable trace. The first write error is kept and later events are
+// dropped, since observers cannot fail the search.
+pub struct TraceObserver<'w, W: Write> {
+    out: &'w mut W,
+    cfg: TraceConfig,
+    instance: Option<Instance>,
+    error: Option<std::io::Error>,
+}
+
+impl<'w, W: Write> TraceObserver<'w, W> {
+    pub fn new(out: &'w mut W, cfg: TraceConfig) -> Self {
+        Self {
+            out,
+            cfg,
+            instance: None,
+            error: None,
+        }
+    }
+
+    pub fn finish(self) -> std::io::Result<()> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    fn write_event(&mut self, event: &SearchEvent<'_>) -> std::io::Result<()> {
+        let out = &mut *self.out;
+        match *event {
+            SearchEvent::Init {
+                instance,
+                seed,
+                params,
+                lower_bound,
+                order,
+                packing,
+                score,
+            } => {
+                self.instance = Some(instance.clone());
+                writeln!(out, "TRACE: Tabu Search ({})", instance.name)?;
+                writeln!(
+                    out,
+                    "instance={} capacity={} n={} seed={}",
+                    instance.name,
+                    instance.capacity,
+                    instance.sizes.len(),
+                    seed
+                )?;
+                writeln!(
+                    out,
+                    "params: max_iters={} neighborhood_samples={} tabu_tenure={} stagnation_limit={} time_limit={:?} decoder={} objective={}",
+                    params.max_iters,
+                    params.neighborhood_samples,
+                    params.tabu_tenure,
+                    params.stagnation_limit,
+                    params.time_limit,
+                    params.decoder.name(),
+                    params.objective.name()
+                )?;
+                writeln!(out, "lower_bound_bins={}", lower_bound)?;
+                writeln!(out, "\ninit permutation (item:size): {}", format_order(instance, order))?;
+                writeln!(out, "init objective: bins={} score={:.4}", score.0, score.1)?;
+                if self.cfg.show_packings {
+                    writeln!(out, "  init packing:")?;
+                    write_packing(out, instance, packing)?;
+                }
+            }
+            SearchEvent::IterationStart {
+                it,
+                current,
+                best,
+                tabu_len,
+            } => {
+                writeln!(
+                    out,
+                    "\n-- it={} -- current bins={} score={:.4} best bins={} score={:.4} tabu_size={}",
+                    it, current.0, current.1, best.0, best.1, tabu_len
+                )?;
+            }
+            SearchEvent::CandidateEvaluated {
+                sample,
+                from,
+                to,
+                items: (item_i, item_j),
+                mv,
+                score,
+                is_tabu,
+                aspiration,
+                allowed,
+            } => {
+                if !self.cfg.show_candidates {
+                    return Ok(());
+                }
+                let Some(instance) = self.instance.as_ref() else {
+                    return Ok(());
+                };
+                let mv_desc = match mv {
+                    MoveKey::Swap { .. } => format!(
+                        "swap pos {}<->{}  items {}:{} <-> {}:{}",
+                        from,
+                        to,
+                        item_i + 1,
+                        instance.sizes[item_i],
+                        item_j + 1,
+                        instance.sizes[item_j]
+                    ),
+                    MoveKey::Insert { .. } => format!(
+                        "insert from pos {} to {}  item {}:{}",
+                        from,
+                        to,
+                        item_i + 1,
+                        instance.sizes[item_i]
+                    ),
+                };
+                writeln!(
+                    out,
+                    "  sample#{:03}: {:45} -> bins={} score={:.4} tabu={} aspiration={} allowed={}",
+                    sample + 1,
+                    mv_desc,
+                    score.0,
+                    score.1,
+                    is_tabu,
+                    aspiration,
+                    allowed
+                )?;
+            }
+            SearchEvent::NoAdmissibleCandidate { .. } => {
+                writeln!(out, "  no admissible candidate found")?;
+            }
+            SearchEvent::MoveChosen { mv, score, packing, .. } => {
+                let Some(instance) = self.instance.as_ref() else {
+                    return Ok(());
+                };
+                let chosen_desc = match mv {
+                    MoveKey::Swap { a, b } => format!(
+                        "chosen move: swap items {}:{} and {}:{}",
+                        a + 1,
+                        instance.sizes[a],
+                        b + 1,
+                        instance.sizes[b]
+                    ),
+                    MoveKey::Insert { item, pos } => format!(
+                        "chosen move: insert item {}:{} to position {}",
+                        item + 1,
+                        instance.sizes[item],
+                        pos
+                    ),
+                };
+                writeln!(out, "  {}", chosen_desc)?;
+                writeln!(out, "  new current: bins={} score={:.4}", score.0, score.1)?;
+                if self.cfg.show_packings {
+                    writeln!(out, "  packing after move:")?;
+                    write_packing(out, instance, packing)?;
+                }
+            }
+            SearchEvent::NewBest { it, score } => {
+                writeln!(out, "  NEW BEST at it={}: bins={} score={:.4}", it, score.0, score.1)?;
+            }
+            SearchEvent::Diversification { it } => {
+                writeln!(
+                    out,
+                    "\nit={}: stagnation reached, diversify: shuffle(best_order) + clear tabu",
+                    it
+                )?;
+            }
+            SearchEvent::Stop {
+                it,
+                reason,
+                best,
+                result,
+            } => {
+                match reason {
+                    StopReason::TimeLimit => writeln!(out, "\nstop: time_limit reached at it={}", it)?,
+                    StopReason::LowerBound => writeln!(out, "stop: reached lower bound on bins")?,
+                    StopReason::MaxIters => writeln!(out, "\nstop: max_iters reached")?,
+                }
+                writeln!(
+                    out,
+                    "\nDONE: elapsed={:.4}s iters={} iters/s={:.1}",
+                    result.elapsed.as_secs_f64(),
+                    result.iters,
+                    result.iters_per_sec
+                )?;
+                writeln!(out, "best: bins={} score={:.4}", best.0, best.1)?;
+                if let Some(instance) = self.instance.as_ref() {
+                    writeln!(
+                        out,
+                        "best permutation (item:size): {}",
+                        format_order(instance, &result.best_order)
+                    )?;
+                    if self.cfg.show_packings {
+                        writeln!(out, "best packing:")?;
+                        write_packing(out, instance, &result.best_packing)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> SearchObserver for TraceObserver<'_, W> {
+    fn on_event(&mut self, event: &SearchEvent<'_>) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self.write_event(event) {
+            self.error = Some(e);
+        }
+    }
+}
+
 fn format_order(instance: &Instance, order: &[usize]) -> String {
     order
         .iter()
@@ -71,7 +398,7 @@ fn write_packing<W: Write>(w: &mut W, instance: &Instance, packing: &Packing) ->
     Ok(())
 }
 
-fn tabu_push<K: Copy + Eq + Hash>(queue: &mut VecDeque<K>, set: &mut HashSet<K>, key: K, max_len: usize) {
+pub(crate) fn tabu_push<K: Copy + Eq + Hash>(queue: &mut VecDeque<K>, set: &mut HashSet<K>, key: K, max_len: usize) {
     if max_len == 0 {
         return;
     }
@@ -84,13 +411,13 @@ fn tabu_push<K: Copy + Eq + Hash>(queue: &mut VecDeque<K>, set: &mut HashSet<K>,
     set.insert(key);
 }
 
-fn apply_swap(order: &[usize], i: usize, j: usize) -> Vec<usize> {
+pub(crate) fn apply_swap(order: &[usize], i: usize, j: usize) -> Vec<usize> {
     let mut out = order.to_vec();
     out.swap(i, j);
     out
 }
 
-fn apply_insert(order: &[usize], i: usize, j: usize) -> Vec<usize> {
+pub(crate) fn apply_insert(order: &[usize], i: usize, j: usize) -> Vec<usize> {
     if i == j {
         return order.to_vec();
     }
@@ -100,153 +427,162 @@ fn apply_insert(order: &[usize], i: usize, j: usize) -> Vec<usize> {
     out
 }
 
-pub fn tabu_search(instance: &Instance, seed: u64, params: TabuParams) -> TabuResult {
-    let n = instance.sizes.len();
-    let start = Instant::now();
-    let mut rng = XorShift64::new(seed);
+pub(crate) fn iters_per_sec(iters: u32, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return 0.0;
+    }
+    iters as f64 / secs
+}
+
+// For every item, the first item of the same size. Identical items are interchangeable, so
+// tabu attributes refer to these instead of the items themselves.
+fn size_representatives(instance: &Instance) -> Vec<usize> {
+    let mut first: HashMap<u32, usize> = HashMap::new();
+    instance
+        .sizes
+        .iter()
+        .enumerate()
+        .map(|(i, &size)| *first.entry(size).or_insert(i))
+        .collect()
+}
 
-    // Strong baseline: decreasing sizes with deterministic tie-breaking.
+// Strong baseline: decreasing sizes with deterministic tie-breaking.
+pub(crate) fn decreasing_order(instance: &Instance, rng: &mut XorShift64) -> Vec<usize> {
+    let n = instance.sizes.len();
     let mut items: Vec<usize> = (0..n).collect();
     let tiebreak: Vec<u64> = (0..n).map(|_| rng.next_u64()).collect();
     items.sort_by_key(|&i| (std::cmp::Reverse(instance.sizes[i]), tiebreak[i]));
-    let mut current = items;
-
-    let mut current_pack = try_reduce_bins(instance, &best_fit_pack(instance, &current));
-    let mut current_obj = packing_objective(&current_pack);
-
-    let mut best_order = current.clone();
-    let mut best_pack = current_pack.clone();
-    let mut best_obj = current_obj;
-    let mut best_iter: u32 = 0;
-
-    let mut tabu_q: VecDeque<MoveKey> = VecDeque::new();
-    let mut tabu_set: HashSet<MoveKey> = HashSet::new();
+    items
+}
 
-    let lb = lower_bound_bins(instance);
-    let mut last_it = 0;
+pub(crate) struct Candidate {
+    pub order: Vec<usize>,
+    pub score: (usize, f64),
+    pub mv: MoveKey,
+    pub packing: Packing,
+    pub first_changed: usize,
+}
 
-    for it in 1..=params.max_iters {
-        last_it = it;
-        if let Some(limit) = params.time_limit {
-            if start.elapsed() >= limit {
-                break;
-            }
-        }
+// The neighbourhood step shared by the permutation searches.
+pub(crate) struct MoveSampler<'a> {
+    instance: &'a Instance,
+    params: &'a TabuParams,
+    rep: Vec<usize>,
+}
 
-        if it.saturating_sub(best_iter) >= params.stagnation_limit {
-            current = best_order.clone();
-            rng.shuffle(&mut current);
-            tabu_q.clear();
-            tabu_set.clear();
+impl<'a> MoveSampler<'a> {
+    pub fn new(instance: &'a Instance, params: &'a TabuParams) -> Self {
+        Self {
+            instance,
+            params,
+            rep: size_representatives(instance),
         }
+    }
 
-        let mut best_candidate: Option<Vec<usize>> = None;
-        let mut best_candidate_obj: Option<(usize, u32)> = None;
-        let mut best_candidate_move: Option<MoveKey> = None;
-        let mut best_candidate_pack: Option<Packing> = None;
-
-        for _ in 0..params.neighborhood_samples {
+    // `params.neighborhood_samples` random swaps and inserts around `current`; returns the best
+    // admissible one. A tabu move is admissible only if it beats `best`.
+    pub fn best_move(
+        &self,
+        current: &[usize],
+        cache: &PrefixCache,
+        tabu_set: &HashSet<MoveKey>,
+        best: (usize, f64),
+        rng: &mut XorShift64,
+        observer: &mut dyn SearchObserver,
+    ) -> Option<Candidate> {
+        let (instance, params, rep) = (self.instance, self.params, &self.rep);
+        let n = current.len();
+        let mut best_candidate: Option<Candidate> = None;
+        for s in 0..params.neighborhood_samples {
             let move_is_swap = rng.gen_f64() < 0.6;
             let i = rng.gen_range_usize(n);
             let j = rng.gen_range_usize(n);
-            if i == j {
+            // Swapping two pieces of the same size decodes to the same packing.
+            if i == j || (move_is_swap && rep[current[i]] == rep[current[j]]) {
                 continue;
             }
 
             let (candidate, mv) = if move_is_swap {
-                let a = current[i];
-                let b = current[j];
+                let a = rep[current[i]];
+                let b = rep[current[j]];
                 let (a, b) = if a < b { (a, b) } else { (b, a) };
-                (apply_swap(&current, i, j), MoveKey::Swap { a, b })
+                (apply_swap(current, i, j), MoveKey::Swap { a, b })
             } else {
-                let item = current[i];
-                (apply_insert(&current, i, j), MoveKey::Insert { item, pos: j })
+                let item = rep[current[i]];
+                (apply_insert(current, i, j), MoveKey::Insert { item, pos: j })
             };
 
-            let pack = try_reduce_bins(instance, &best_fit_pack(instance, &candidate));
-            let obj = packing_objective(&pack);
+            let first_changed = i.min(j);
+            let pack = try_reduce_bins(instance, &cache.evaluate(instance, &candidate, first_changed));
+            let obj = params.objective.score(&pack);
 
             let is_tabu = tabu_set.contains(&mv);
-            let aspiration = obj < best_obj;
-            if is_tabu && !aspiration {
+            let aspiration = obj < best;
+            let allowed = !is_tabu || aspiration;
+            observer.on_event(&SearchEvent::CandidateEvaluated {
+                sample: s,
+                from: i,
+                to: j,
+                items: (current[i], current[j]),
+                mv,
+                score: obj,
+                is_tabu,
+                aspiration,
+                allowed,
+            });
+            if !allowed {
                 continue;
             }
 
-            if best_candidate_obj.is_none() || obj < best_candidate_obj.unwrap() {
-                best_candidate = Some(candidate);
-                best_candidate_obj = Some(obj);
-                best_candidate_move = Some(mv);
-                best_candidate_pack = Some(pack);
-            }
-        }
-
-        let Some(candidate) = best_candidate else { continue };
-        current = candidate;
-        current_pack = best_candidate_pack.unwrap();
-        current_obj = best_candidate_obj.unwrap();
-        tabu_push(&mut tabu_q, &mut tabu_set, best_candidate_move.unwrap(), params.tabu_tenure);
-
-        if current_obj < best_obj {
-            best_obj = current_obj;
-            best_order = current.clone();
-            best_pack = current_pack.clone();
-            best_iter = it;
-            if best_obj.0 == lb {
-                break;
+            if best_candidate.as_ref().is_none_or(|c| obj < c.score) {
+                best_candidate = Some(Candidate {
+                    order: candidate,
+                    score: obj,
+                    mv,
+                    packing: pack,
+                    first_changed,
+                });
             }
         }
+        best_candidate
     }
+}
 
-    TabuResult {
-        best_order,
-        best_packing: best_pack,
-        best_bins: best_obj.0,
-        best_unused: best_obj.1,
-        elapsed: start.elapsed(),
-        iters: last_it,
+pub fn tabu_search(instance: &Instance, seed: u64, params: TabuParams) -> TabuResult {
+    if params.space == SearchSpace::Bins {
+        return bin_tabu_search(instance, seed, params);
     }
+    tabu_search_observed(instance, seed, params, &mut NoopObserver)
 }
 
-pub fn tabu_search_trace<W: Write>(
+// Permutation-space tabu search reporting every step to `observer`; `params.space` is ignored.
+pub fn tabu_search_observed(
     instance: &Instance,
     seed: u64,
     params: TabuParams,
-    cfg: TraceConfig,
-    out: &mut W,
-) -> std::io::Result<TabuResult> {
-    let n = instance.sizes.len();
+    observer: &mut dyn SearchObserver,
+) -> TabuResult {
     let start = Instant::now();
     let mut rng = XorShift64::new(seed);
 
-    writeln!(out, "TRACE: Tabu Search (TP2 instance trace)")?;
-    writeln!(
-        out,
-        "instance={} capacity={} n={} seed={}",
-        instance.name, instance.capacity, n, seed
-    )?;
-    writeln!(
-        out,
-        "params: max_iters={} neighborhood_samples={} tabu_tenure={} stagnation_limit={} time_limit={:?}",
-        params.max_iters, params.neighborhood_samples, params.tabu_tenure, params.stagnation_limit, params.time_limit
-    )?;
-
-    let lb = lower_bound_bins(instance);
-    writeln!(out, "lower_bound_bins={}", lb)?;
+    let mut current = decreasing_order(instance, &mut rng);
+    let mut cache = PrefixCache::new(instance, params.decoder.decoder(), &current);
 
-    let mut items: Vec<usize> = (0..n).collect();
-    let tiebreak: Vec<u64> = (0..n).map(|_| rng.next_u64()).collect();
-    items.sort_by_key(|&i| (std::cmp::Reverse(instance.sizes[i]), tiebreak[i]));
-    let mut current = items;
+    let mut current_pack = try_reduce_bins(instance, &cache.packing(instance));
+    let mut current_obj = params.objective.score(&current_pack);
 
-    let mut current_pack = try_reduce_bins(instance, &best_fit_pack(instance, &current));
-    let mut current_obj = packing_objective(&current_pack);
-
-    writeln!(out, "\ninit permutation (item:size): {}", format_order(instance, &current))?;
-    writeln!(out, "init objective: bins={} unused={}", current_obj.0, current_obj.1)?;
-    if cfg.show_packings {
-        writeln!(out, "  init packing:")?;
-        write_packing(out, instance, &current_pack)?;
-    }
+    let lb = best_lower_bound(instance);
+    let sampler = MoveSampler::new(instance, &params);
+    observer.on_event(&SearchEvent::Init {
+        instance,
+        seed,
+        params: &params,
+        lower_bound: lb,
+        order: &current,
+        packing: &current_pack,
+        score: current_obj,
+    });
 
     let mut best_order = current.clone();
     let mut best_pack = current_pack.clone();
@@ -257,164 +593,396 @@ pub fn tabu_search_trace<W: Write>(
     let mut tabu_set: HashSet<MoveKey> = HashSet::new();
 
     let mut last_it = 0;
+    let mut reason = StopReason::MaxIters;
 
     for it in 1..=params.max_iters {
         last_it = it;
         if let Some(limit) = params.time_limit {
             if start.elapsed() >= limit {
-                writeln!(out, "\nstop: time_limit reached at it={}", it)?;
+                reason = StopReason::TimeLimit;
                 break;
             }
         }
 
         if it.saturating_sub(best_iter) >= params.stagnation_limit {
-            writeln!(out, "\nit={}: stagnation reached, diversify: shuffle(best_order) + clear tabu", it)?;
+            observer.on_event(&SearchEvent::Diversification { it });
             current = best_order.clone();
             rng.shuffle(&mut current);
+            cache.rebuild_from(instance, &current, 0);
             tabu_q.clear();
             tabu_set.clear();
         }
 
-        writeln!(
-            out,
-            "\n-- it={} -- current bins={} unused={} best bins={} unused={} tabu_size={}",
+        observer.on_event(&SearchEvent::IterationStart {
             it,
-            current_obj.0,
-            current_obj.1,
-            best_obj.0,
-            best_obj.1,
-            tabu_set.len()
-        )?;
-
-        let mut best_candidate: Option<Vec<usize>> = None;
-        let mut best_candidate_obj: Option<(usize, u32)> = None;
-        let mut best_candidate_move: Option<MoveKey> = None;
-        let mut best_candidate_pack: Option<Packing> = None;
+            current: current_obj,
+            best: best_obj,
+            tabu_len: tabu_set.len(),
+        });
 
-        for s in 0..params.neighborhood_samples {
-            let move_is_swap = rng.gen_f64() < 0.6;
-            let i = rng.gen_range_usize(n);
-            let j = rng.gen_range_usize(n);
-            if i == j {
-                continue;
+        let Some(chosen) = sampler.best_move(&current, &cache, &tabu_set, best_obj, &mut rng, observer) else {
+            observer.on_event(&SearchEvent::NoAdmissibleCandidate { it });
+            continue;
+        };
+        current = chosen.order;
+        cache.rebuild_from(instance, &current, chosen.first_changed);
+        current_pack = chosen.packing;
+        current_obj = chosen.score;
+        tabu_push(&mut tabu_q, &mut tabu_set, chosen.mv, params.tabu_tenure);
+        observer.on_event(&SearchEvent::MoveChosen {
+            it,
+            mv: chosen.mv,
+            score: current_obj,
+            packing: &current_pack,
+        });
+
+        if current_obj < best_obj {
+            best_obj = current_obj;
+            best_order = current.clone();
+            best_pack = current_pack.clone();
+            best_iter = it;
+            observer.on_event(&SearchEvent::NewBest { it, score: best_obj });
+            if best_obj.0 == lb {
+                reason = StopReason::LowerBound;
+                break;
             }
+        }
+    }
 
-            let (candidate, mv, mv_desc) = if move_is_swap {
-                let item_i = current[i];
-                let item_j = current[j];
-                let (a, b) = if item_i < item_j { (item_i, item_j) } else { (item_j, item_i) };
-                (
-                    apply_swap(&current, i, j),
-                    MoveKey::Swap { a, b },
-                    format!(
-                        "swap pos {}<->{}  items {}:{} <-> {}:{}",
-                        i,
-                        j,
-                        item_i + 1,
-                        instance.sizes[item_i],
-                        item_j + 1,
-                        instance.sizes[item_j]
-                    ),
-                )
-            } else {
-                let item = current[i];
-                (
-                    apply_insert(&current, i, j),
-                    MoveKey::Insert { item, pos: j },
-                    format!(
-                        "insert from pos {} to {}  item {}:{}",
-                        i,
-                        j,
-                        item + 1,
-                        instance.sizes[item]
-                    ),
-                )
-            };
+    let elapsed = start.elapsed();
+    let best_unused = packing_objective(&best_pack).1;
+    let result = TabuResult {
+        best_order,
+        best_packing: best_pack,
+        best_bins: best_obj.0,
+        best_unused,
+        elapsed,
+        iters: last_it,
+        iters_per_sec: iters_per_sec(last_it, elapsed),
+        stop_reason: reason,
+    };
+    observer.on_event(&SearchEvent::Stop {
+        it: last_it,
+        reason,
+        best: best_obj,
+        result: &result,
+    });
+    result
+}
 
-            let pack = try_reduce_bins(instance, &best_fit_pack(instance, &candidate));
-            let obj = packing_objective(&pack);
+// Moves of the bin-space search. Every moved item leaves its origin bin, and moving it (or an
+// item of the same size) back there is what the tabu list forbids.
+#[derive(Clone, Copy, Debug)]
+enum BinMove {
+    Shift { item: usize, to: usize },
+    Swap { a: usize, b: usize },
+    PairForOne { a1: usize, a2: usize, b: usize },
+}
 
-            let is_tabu = tabu_set.contains(&mv);
-            let aspiration = obj < best_obj;
-            let allowed = !is_tabu || aspiration;
+// A packing plus the bin of every item. Bins emptied by a move keep their index so that
+// tabu attributes stay meaningful; they are dropped only when a packing is handed out.
+#[derive(Clone)]
+struct BinState {
+    packing: Packing,
+    bin_of: Vec<usize>,
+}
 
-            if cfg.show_candidates {
-                writeln!(
-                    out,
-                    "  sample#{:03}: {:45} -> bins={} unused={} tabu={} aspiration={} allowed={}",
-                    s + 1,
-                    mv_desc,
-                    obj.0,
-                    obj.1,
-                    is_tabu,
-                    aspiration,
-                    allowed
-                )?;
+impl BinState {
+    fn new(instance: &Instance, packing: Packing) -> Self {
+        let mut bin_of = vec![0; instance.sizes.len()];
+        for (b, items) in packing.bins.iter().enumerate() {
+            for &i in items {
+                bin_of[i] = b;
             }
+        }
+        Self { packing, bin_of }
+    }
 
-            if !allowed {
-                continue;
+    fn relocate(&mut self, instance: &Instance, item: usize, to: usize) {
+        let from = self.bin_of[item];
+        let pos = self.packing.bins[from].iter().position(|&i| i == item).unwrap();
+        self.packing.bins[from].swap_remove(pos);
+        self.packing.bin_loads[from] -= instance.sizes[item];
+        self.packing.bins[to].push(item);
+        self.packing.bin_loads[to] += instance.sizes[item];
+        self.bin_of[item] = to;
+    }
+
+    // Returns the (item, origin bin) pairs of the applied move.
+    fn apply(&mut self, instance: &Instance, mv: BinMove) -> Vec<(usize, usize)> {
+        match mv {
+            BinMove::Shift { item, to } => {
+                let from = self.bin_of[item];
+                self.relocate(instance, item, to);
+                vec![(item, from)]
+            }
+            BinMove::Swap { a, b } => {
+                let (ba, bb) = (self.bin_of[a], self.bin_of[b]);
+                self.relocate(instance, a, bb);
+                self.relocate(instance, b, ba);
+                vec![(a, ba), (b, bb)]
+            }
+            BinMove::PairForOne { a1, a2, b } => {
+                let (ba, bb) = (self.bin_of[a1], self.bin_of[b]);
+                self.relocate(instance, a1, bb);
+                self.relocate(instance, a2, bb);
+                self.relocate(instance, b, ba);
+                vec![(a1, ba), (a2, ba), (b, bb)]
             }
+        }
+    }
+
+    fn undo(&mut self, instance: &Instance, moved: &[(usize, usize)]) {
+        for &(item, from) in moved {
+            self.relocate(instance, item, from);
+        }
+    }
 
-            if best_candidate_obj.is_none() || obj < best_candidate_obj.unwrap() {
-                best_candidate = Some(candidate);
-                best_candidate_obj = Some(obj);
-                best_candidate_move = Some(mv);
-                best_candidate_pack = Some(pack);
+    // (item, destination bin) pairs of a move, checked against the tabu attributes.
+    fn destinations(&self, mv: BinMove) -> Vec<(usize, usize)> {
+        match mv {
+            BinMove::Shift { item, to } => vec![(item, to)],
+            BinMove::Swap { a, b } => vec![(a, self.bin_of[b]), (b, self.bin_of[a])],
+            BinMove::PairForOne { a1, a2, b } => {
+                let (ba, bb) = (self.bin_of[a1], self.bin_of[b]);
+                vec![(a1, bb), (a2, bb), (b, ba)]
             }
         }
+    }
 
-        let Some(candidate) = best_candidate else {
-            writeln!(out, "  no admissible candidate found")?;
-            continue;
-        };
+    fn sample_move(&self, instance: &Instance, rng: &mut XorShift64) -> Option<BinMove> {
+        let n = instance.sizes.len();
+        let cap = instance.capacity;
+        let loads = &self.packing.bin_loads;
+        let r = rng.gen_f64();
+
+        if r < 0.5 {
+            let item = rng.gen_range_usize(n);
+            let to = rng.gen_range_usize(loads.len());
+            let from = self.bin_of[item];
+            // Opening a fresh bin never helps, so empty bins are not targets.
+            if to == from || loads[to] == 0 || loads[to] + instance.sizes[item] > cap {
+                return None;
+            }
+            Some(BinMove::Shift { item, to })
+        } else if r < 0.85 {
+            let a = rng.gen_range_usize(n);
+            let b = rng.gen_range_usize(n);
+            let (ba, bb) = (self.bin_of[a], self.bin_of[b]);
+            let (sa, sb) = (instance.sizes[a], instance.sizes[b]);
+            if ba == bb || sa == sb || loads[ba] - sa + sb > cap || loads[bb] - sb + sa > cap {
+                return None;
+            }
+            Some(BinMove::Swap { a, b })
+        } else {
+            let a1 = rng.gen_range_usize(n);
+            let ba = self.bin_of[a1];
+            let bin_a = &self.packing.bins[ba];
+            if bin_a.len() < 2 {
+                return None;
+            }
+            let a2 = bin_a[rng.gen_range_usize(bin_a.len())];
+            let b = rng.gen_range_usize(n);
+            let bb = self.bin_of[b];
+            if a2 == a1 || bb == ba {
+                return None;
+            }
+            let pair = instance.sizes[a1] + instance.sizes[a2];
+            let sb = instance.sizes[b];
+            if pair == sb || loads[bb] - sb + pair > cap || loads[ba] - pair + sb > cap {
+                return None;
+            }
+            Some(BinMove::PairForOne { a1, a2, b })
+        }
+    }
+
+    fn compacted(&self) -> Packing {
+        let mut bins = Vec::new();
+        let mut bin_loads = Vec::new();
+        for (items, &load) in self.packing.bins.iter().zip(self.packing.bin_loads.iter()) {
+            if !items.is_empty() {
+                bins.push(items.clone());
+                bin_loads.push(load);
+            }
+        }
+        Packing {
+            capacity: self.packing.capacity,
+            bins,
+            bin_loads,
+        }
+    }
+}
 
-        current = candidate;
-        current_pack = best_candidate_pack.unwrap();
-        current_obj = best_candidate_obj.unwrap();
+// Tabu search directly on packings: shift an item to another bin, swap two items across bins,
+// or swap a pair of items for a single item. Returns the best packing's bins concatenated as
+// `best_order`.
+pub fn bin_tabu_search(instance: &Instance, seed: u64, params: TabuParams) -> TabuResult {
+    let n = instance.sizes.len();
+    let start = Instant::now();
+    let mut rng = XorShift64::new(seed);
 
-        let chosen_move = best_candidate_move.unwrap();
-        let chosen_desc = match chosen_move {
-            MoveKey::Swap { a, b } => format!("chosen move: swap items {}:{} and {}:{}", a + 1, instance.sizes[a], b + 1, instance.sizes[b]),
-            MoveKey::Insert { item, pos } => format!("chosen move: insert item {}:{} to position {}", item + 1, instance.sizes[item], pos),
-        };
-        writeln!(out, "  {}", chosen_desc)?;
-        writeln!(out, "  new current: bins={} unused={}", current_obj.0, current_obj.1)?;
+    let order = decreasing_order(instance, &mut rng);
+    let init = try_reduce_bins(instance, &params.decoder.decoder().pack(instance, &order));
+    let mut current = BinState::new(instance, init);
+    let rep = size_representatives(instance);
+
+    let mut best_pack = current.compacted();
+    let mut best_obj = params.objective.score(&current.packing);
+    let mut best_iter: u32 = 0;
+    let mut last_restart: u32 = 0;
 
-        tabu_push(&mut tabu_q, &mut tabu_set, chosen_move, params.tabu_tenure);
+    let mut tabu_q: VecDeque<(usize, usize)> = VecDeque::new();
+    let mut tabu_set: HashSet<(usize, usize)> = HashSet::new();
 
-        if cfg.show_packings {
-            writeln!(out, "  packing after move:")?;
-            write_packing(out, instance, &current_pack)?;
+    let lb = best_lower_bound(instance);
+    let mut last_it = 0;
+    let mut reason = StopReason::MaxIters;
+
+    for it in 1..=params.max_iters {
+        if best_obj.0 == lb || n < 2 {
+            reason = StopReason::LowerBound;
+            break;
+        }
+        last_it = it;
+        if let Some(limit) = params.time_limit {
+            if start.elapsed() >= limit {
+                reason = StopReason::TimeLimit;
+                break;
+            }
         }
 
-        if current_obj < best_obj {
-            best_obj = current_obj;
-            best_order = current.clone();
-            best_pack = current_pack.clone();
-            best_iter = it;
-            writeln!(out, "  NEW BEST at it={}: bins={} unused={}", it, best_obj.0, best_obj.1)?;
-            if best_obj.0 == lb {
-                writeln!(out, "stop: reached lower bound on bins")?;
+        if it.saturating_sub(best_iter.max(last_restart)) >= params.stagnation_limit {
+            // Restart from the best packing, kicked by random feasible swaps.
+            current = BinState::new(instance, best_pack.clone());
+            let mut kicks = n / 10 + 1;
+            for _ in 0..n {
+                if kicks == 0 {
+                    break;
+                }
+                if let Some(mv @ BinMove::Swap { .. }) = current.sample_move(instance, &mut rng) {
+                    current.apply(instance, mv);
+                    kicks -= 1;
+                }
+            }
+            last_restart = it;
+            tabu_q.clear();
+            tabu_set.clear();
+        }
+
+        // Most random moves overflow a bin, so sampling retries until enough feasible moves
+        // were scored (with a cap for packings where hardly anything fits).
+        let mut best_candidate: Option<(BinMove, (usize, f64))> = None;
+        let mut scored: u32 = 0;
+        for _ in 0..params.neighborhood_samples.saturating_mul(10) {
+            if scored == params.neighborhood_samples {
                 break;
             }
+            let Some(mv) = current.sample_move(instance, &mut rng) else {
+                continue;
+            };
+            scored += 1;
+            let moved = current.apply(instance, mv);
+            let obj = params.objective.score(&current.packing);
+            current.undo(instance, &moved);
+
+            let is_tabu = current
+                .destinations(mv)
+                .iter()
+                .any(|&(item, bin)| tabu_set.contains(&(rep[item], bin)));
+            if is_tabu && obj >= best_obj {
+                continue;
+            }
+            if best_candidate.is_none_or(|(_, c)| obj < c) {
+                best_candidate = Some((mv, obj));
+            }
+        }
+
+        let Some((mv, obj)) = best_candidate else { continue };
+        for (item, from) in current.apply(instance, mv) {
+            tabu_push(&mut tabu_q, &mut tabu_set, (rep[item], from), params.tabu_tenure);
         }
-    }
 
-    writeln!(out, "\nDONE: elapsed={:.4}s iters={}", start.elapsed().as_secs_f64(), last_it)?;
-    writeln!(out, "best: bins={} unused={}", best_obj.0, best_obj.1)?;
-    writeln!(out, "best permutation (item:size): {}", format_order(instance, &best_order))?;
-    if cfg.show_packings {
-        writeln!(out, "best packing:")?;
-        write_packing(out, instance, &best_pack)?;
+        if obj < best_obj {
+            best_obj = obj;
+            best_pack = current.compacted();
+            best_iter = it;
+        }
     }
 
-    Ok(TabuResult {
-        best_order,
+    let elapsed = start.elapsed();
+    let best_unused = packing_objective(&best_pack).1;
+    TabuResult {
+        best_order: best_pack.bins.concat(),
+        best_bins: best_pack.n_bins(),
         best_packing: best_pack,
-        best_bins: best_obj.0,
-        best_unused: best_obj.1,
-        elapsed: start.elapsed(),
+        best_unused,
+        elapsed,
         iters: last_it,
-    })
+        iters_per_sec: iters_per_sec(last_it, elapsed),
+        stop_reason: reason,
+    }
+}
+
+pub fn tabu_search_trace<W: Write>(
+    instance: &Instance,
+    seed: u64,
+    params: TabuParams,
+    cfg: TraceConfig,
+    out: &mut W,
+) -> std::io::Result<TabuResult> {
+    let mut observer = TraceObserver::new(out, cfg);
+    let result = tabu_search_observed(instance, seed, params, &mut observer);
+    observer.finish()?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instances::{example_instance_tp2, synthetic_instance};
+    use crate::packing::validate_packing;
+
+    #[test]
+    fn bin_space_search_returns_valid_packings() {
+        let params = TabuParams {
+            max_iters: 300,
+            space: SearchSpace::Bins,
+            ..TabuParams::default()
+        };
+
+        let tp2 = example_instance_tp2();
+        let res = tabu_search(&tp2, 0, params);
+        validate_packing(&tp2, &res.best_packing).unwrap();
+        assert_eq!(res.best_bins, 4);
+
+        let inst = synthetic_instance("bin-space", 120, 150, 10, 100, 4);
+        let res = bin_tabu_search(&inst, 1, params);
+        validate_packing(&inst, &res.best_packing).unwrap();
+        assert_eq!(res.best_bins, res.best_packing.n_bins());
+        assert_eq!(res.best_order.len(), inst.sizes.len());
+    }
+
+    #[test]
+    fn trace_observer_does_not_change_the_search() {
+        let inst = synthetic_instance("observed", 60, 150, 10, 100, 9);
+        let params = TabuParams {
+            max_iters: 200,
+            stagnation_limit: 50,
+            ..TabuParams::default()
+        };
+        let plain = tabu_search(&inst, 5, params);
+
+        let cfg = TraceConfig {
+            show_candidates: true,
+            show_packings: true,
+        };
+        let mut out = Vec::new();
+        let traced = tabu_search_trace(&inst, 5, params, cfg, &mut out).unwrap();
+        assert_eq!(plain.best_order, traced.best_order);
+        assert_eq!(plain.iters, traced.iters);
+
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("TRACE: Tabu Search"));
+        assert!(text.contains("DONE:"));
+    }
 }
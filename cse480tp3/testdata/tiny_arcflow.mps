@@ -0,0 +1,61 @@
+NAME tiny
+ROWS
+ N obj
+ E flow_0
+ E flow_4
+ E flow_5
+ E flow_6
+ E flow_9
+ E flow_10
+ G demand_6
+ G demand_5
+ G demand_4
+COLUMNS
+ MARKER 'MARKER' 'INTORG'
+ z obj 1
+ z flow_0 1
+ z flow_10 -1
+ f_0_6 flow_0 -1
+ f_0_6 flow_6 1
+ f_0_6 demand_6 1
+ f_0_5 flow_0 -1
+ f_0_5 flow_5 1
+ f_0_5 demand_5 1
+ f_5_10 flow_5 -1
+ f_5_10 flow_10 1
+ f_5_10 demand_5 1
+ f_0_4 flow_0 -1
+ f_0_4 flow_4 1
+ f_0_4 demand_4 1
+ f_5_9 flow_5 -1
+ f_5_9 flow_9 1
+ f_5_9 demand_4 1
+ f_6_10 flow_6 -1
+ f_6_10 flow_10 1
+ f_6_10 demand_4 1
+ l_4 flow_4 -1
+ l_4 flow_10 1
+ l_5 flow_5 -1
+ l_5 flow_10 1
+ l_6 flow_6 -1
+ l_6 flow_10 1
+ l_9 flow_9 -1
+ l_9 flow_10 1
+ MARKER 'MARKER' 'INTEND'
+RHS
+ RHS demand_6 1
+ RHS demand_5 2
+ RHS demand_4 1
+BOUNDS
+ PL BND z
+ PL BND f_0_6
+ PL BND f_0_5
+ PL BND f_5_10
+ PL BND f_0_4
+ PL BND f_5_9
+ PL BND f_6_10
+ PL BND l_4
+ PL BND l_5
+ PL BND l_6
+ PL BND l_9
+ENDATA
@@ -0,0 +1,52 @@
+NAME tiny
+ROWS
+ N obj
+ E assign_1
+ E assign_2
+ E assign_3
+ E assign_4
+ L cap_1
+ L cap_2
+ G order_2
+COLUMNS
+ MARKER 'MARKER' 'INTORG'
+ y_1 obj 1
+ y_1 cap_1 -10
+ y_1 order_2 1
+ y_2 obj 1
+ y_2 cap_2 -10
+ y_2 order_2 -1
+ x_1_1 assign_1 1
+ x_1_1 cap_1 6
+ x_1_2 assign_1 1
+ x_1_2 cap_2 6
+ x_2_1 assign_2 1
+ x_2_1 cap_1 4
+ x_2_2 assign_2 1
+ x_2_2 cap_2 4
+ x_3_1 assign_3 1
+ x_3_1 cap_1 5
+ x_3_2 assign_3 1
+ x_3_2 cap_2 5
+ x_4_1 assign_4 1
+ x_4_1 cap_1 5
+ x_4_2 assign_4 1
+ x_4_2 cap_2 5
+ MARKER 'MARKER' 'INTEND'
+RHS
+ RHS assign_1 1
+ RHS assign_2 1
+ RHS assign_3 1
+ RHS assign_4 1
+BOUNDS
+ BV BND y_1
+ BV BND y_2
+ BV BND x_1_1
+ BV BND x_1_2
+ BV BND x_2_1
+ BV BND x_2_2
+ BV BND x_3_1
+ BV BND x_3_2
+ BV BND x_4_1
+ BV BND x_4_2
+ENDATA